@@ -0,0 +1,1075 @@
+use crate::array30_data;
+use crate::logic::PracticeMode;
+use crate::robust_stats::OutlierRules;
+use crate::storage::SessionRecord;
+use crate::time_zone::{week_start, TimeZoneChoice};
+use chrono::{DateTime, Days, Duration, NaiveDate, Utc};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// Latency and error totals for one target character across all sessions.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CharacterSummary {
+    pub ch: char,
+    pub code: Option<&'static str>,
+    /// Times the character was reached in an exercise.
+    pub attempts: usize,
+    /// Attempts that ended in a correct commit and so have a latency.
+    pub timed: usize,
+    pub total_latency_ms: u64,
+    pub errors: usize,
+}
+
+impl CharacterSummary {
+    pub fn mean_latency_ms(&self) -> Option<f64> {
+        (self.timed > 0).then(|| self.total_latency_ms as f64 / self.timed as f64)
+    }
+
+    /// Mistypes per attempt; can exceed 1 when a character was missed repeatedly.
+    pub fn error_rate(&self) -> f64 {
+        if self.attempts > 0 {
+            self.errors as f64 / self.attempts as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+/// The same totals grouped by Array30 code, so characters sharing a code are drilled together.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CodeSummary {
+    pub code: &'static str,
+    pub characters: Vec<char>,
+    pub attempts: usize,
+    pub timed: usize,
+    pub total_latency_ms: u64,
+    pub errors: usize,
+}
+
+impl CodeSummary {
+    pub fn mean_latency_ms(&self) -> Option<f64> {
+        (self.timed > 0).then(|| self.total_latency_ms as f64 / self.timed as f64)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CharacterReport {
+    pub characters: Vec<CharacterSummary>,
+    pub codes: Vec<CodeSummary>,
+}
+
+impl CharacterReport {
+    pub fn from_records(records: &[SessionRecord]) -> Self {
+        let mut by_char: BTreeMap<char, CharacterSummary> = BTreeMap::new();
+        for timing in records.iter().flat_map(|record| &record.char_timings) {
+            let summary = by_char
+                .entry(timing.ch)
+                .or_insert_with(|| CharacterSummary {
+                    ch: timing.ch,
+                    code: array30_data::get_array30_code(timing.ch),
+                    ..CharacterSummary::default()
+                });
+            summary.attempts += 1;
+            summary.errors += timing.errors;
+            if let Some(latency_ms) = timing.latency_ms {
+                summary.timed += 1;
+                summary.total_latency_ms += latency_ms;
+            }
+        }
+
+        let mut by_code: BTreeMap<&'static str, CodeSummary> = BTreeMap::new();
+        for summary in by_char.values() {
+            let Some(code) = summary.code else { continue };
+            let group = by_code.entry(code).or_insert_with(|| CodeSummary {
+                code,
+                ..CodeSummary::default()
+            });
+            group.characters.push(summary.ch);
+            group.attempts += summary.attempts;
+            group.timed += summary.timed;
+            group.total_latency_ms += summary.total_latency_ms;
+            group.errors += summary.errors;
+        }
+
+        CharacterReport {
+            characters: by_char.into_values().collect(),
+            codes: by_code.into_values().collect(),
+        }
+    }
+
+    pub fn slowest(&self, limit: usize) -> Vec<&CharacterSummary> {
+        let mut timed: Vec<&CharacterSummary> = self
+            .characters
+            .iter()
+            .filter(|summary| summary.timed > 0)
+            .collect();
+        timed.sort_by(|a, b| {
+            let key = |summary: &CharacterSummary| summary.mean_latency_ms().unwrap_or_default();
+            key(b).total_cmp(&key(a))
+        });
+        timed.truncate(limit);
+        timed
+    }
+
+    pub fn most_error_prone(&self, limit: usize) -> Vec<&CharacterSummary> {
+        let mut missed: Vec<&CharacterSummary> = self
+            .characters
+            .iter()
+            .filter(|summary| summary.errors > 0)
+            .collect();
+        missed.sort_by(|a, b| {
+            b.errors
+                .cmp(&a.errors)
+                .then(b.error_rate().total_cmp(&a.error_rate()))
+        });
+        missed.truncate(limit);
+        missed
+    }
+
+    pub fn slowest_codes(&self, limit: usize) -> Vec<&CodeSummary> {
+        let mut timed: Vec<&CodeSummary> =
+            self.codes.iter().filter(|code| code.timed > 0).collect();
+        timed.sort_by(|a, b| {
+            let key = |code: &CodeSummary| code.mean_latency_ms().unwrap_or_default();
+            key(b).total_cmp(&key(a))
+        });
+        timed.truncate(limit);
+        timed
+    }
+}
+
+/// The 30 Array30 keys in QWERTY layout, top row first.
+pub const KEYBOARD_ROWS: [[char; 10]; 3] = [
+    ['q', 'w', 'e', 'r', 't', 'y', 'u', 'i', 'o', 'p'],
+    ['a', 's', 'd', 'f', 'g', 'h', 'j', 'k', 'l', ';'],
+    ['z', 'x', 'c', 'v', 'b', 'n', 'm', ',', '.', '/'],
+];
+
+pub const FINGER_NAMES: [&str; 8] = [
+    "Left pinky",
+    "Left ring",
+    "Left middle",
+    "Left index",
+    "Right index",
+    "Right middle",
+    "Right ring",
+    "Right pinky",
+];
+
+/// Array30 names keys by column and row, e.g. `q` is 1↑ and `;` is 0-.
+pub fn array30_key_label(key: char) -> Option<String> {
+    let (row, column) = key_position(key)?;
+    let number = (column + 1) % 10;
+    let row_mark = ["↑", "-", "↓"][row];
+    Some(format!("{number}{row_mark}"))
+}
+
+/// Touch-typing finger for a key, as an index into [`FINGER_NAMES`].
+pub fn finger_for_key(key: char) -> Option<usize> {
+    let (_, column) = key_position(key)?;
+    Some(match column {
+        0..=2 => column,
+        3 | 4 => 3,
+        5 | 6 => 4,
+        _ => column - 2,
+    })
+}
+
+fn key_position(key: char) -> Option<(usize, usize)> {
+    KEYBOARD_ROWS.iter().enumerate().find_map(|(row, keys)| {
+        keys.iter()
+            .position(|&candidate| candidate == key)
+            .map(|column| (row, column))
+    })
+}
+
+/// Keys of the primary Array30 code for a character (the first alternative when there are several).
+fn code_keys(ch: char) -> Vec<char> {
+    array30_data::get_array30_code(ch)
+        .and_then(|code| code.split('|').next())
+        .map(|code| {
+            code.chars()
+                .filter(|&key| key_position(key).is_some())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Errors and latency attributed to one physical key.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct KeyStats {
+    /// Keystrokes of correctly committed characters that used this key.
+    pub presses: usize,
+    /// Each character's latency split evenly across the keys of its code.
+    pub total_latency_ms: u64,
+    /// Errors on characters whose expected code uses this key.
+    pub blamed_errors: usize,
+    /// Times this key was part of the code of a wrong character that was typed instead.
+    pub pressed_instead: usize,
+}
+
+impl KeyStats {
+    pub fn mean_latency_ms(&self) -> Option<f64> {
+        (self.presses > 0).then(|| self.total_latency_ms as f64 / self.presses as f64)
+    }
+
+    fn merge(&mut self, other: &KeyStats) {
+        self.presses += other.presses;
+        self.total_latency_ms += other.total_latency_ms;
+        self.blamed_errors += other.blamed_errors;
+        self.pressed_instead += other.pressed_instead;
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct KeyReport {
+    pub keys: BTreeMap<char, KeyStats>,
+}
+
+impl KeyReport {
+    pub fn from_records(records: &[SessionRecord]) -> Self {
+        let mut keys: BTreeMap<char, KeyStats> = BTreeMap::new();
+        for timing in records.iter().flat_map(|record| &record.char_timings) {
+            let expected = code_keys(timing.ch);
+            if let Some(latency_ms) = timing.latency_ms.filter(|_| !expected.is_empty()) {
+                let share = latency_ms / expected.len() as u64;
+                for &key in &expected {
+                    let stats = keys.entry(key).or_default();
+                    stats.presses += 1;
+                    stats.total_latency_ms += share;
+                }
+            }
+            for &key in &expected {
+                keys.entry(key).or_default().blamed_errors += timing.errors;
+            }
+            for wrong in timing.mistyped.chars() {
+                for key in code_keys(wrong) {
+                    keys.entry(key).or_default().pressed_instead += 1;
+                }
+            }
+        }
+
+        KeyReport { keys }
+    }
+
+    pub fn key(&self, key: char) -> KeyStats {
+        self.keys.get(&key).cloned().unwrap_or_default()
+    }
+
+    /// Totals per finger, indexed like [`FINGER_NAMES`].
+    pub fn by_finger(&self) -> [KeyStats; 8] {
+        let mut fingers: [KeyStats; 8] = Default::default();
+        for (&key, stats) in &self.keys {
+            if let Some(finger) = finger_for_key(key) {
+                fingers[finger].merge(stats);
+            }
+        }
+        fingers
+    }
+
+    /// Totals per keyboard row, top row first.
+    pub fn by_row(&self) -> [KeyStats; 3] {
+        let mut rows: [KeyStats; 3] = Default::default();
+        for (&key, stats) in &self.keys {
+            if let Some((row, _)) = key_position(key) {
+                rows[row].merge(stats);
+            }
+        }
+        rows
+    }
+}
+
+/// One (expected, typed) pair and how often it happened.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Confusion {
+    pub expected: char,
+    pub typed: char,
+    pub count: usize,
+    pub expected_code: Option<&'static str>,
+    pub typed_code: Option<&'static str>,
+}
+
+impl Confusion {
+    /// Key positions where the two primary codes differ, as (position, expected key, typed key).
+    /// Empty when both characters share a code and only the candidate choice went wrong.
+    pub fn differing_keys(&self) -> Vec<(usize, Option<char>, Option<char>)> {
+        let expected = code_keys(self.expected);
+        let typed = code_keys(self.typed);
+        (0..expected.len().max(typed.len()))
+            .map(|i| (i, expected.get(i).copied(), typed.get(i).copied()))
+            .filter(|(_, a, b)| a != b)
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConfusionReport {
+    /// Most frequent first.
+    pub confusions: Vec<Confusion>,
+}
+
+impl ConfusionReport {
+    pub fn from_records(records: &[SessionRecord]) -> Self {
+        let mut counts: BTreeMap<(char, char), usize> = BTreeMap::new();
+        for timing in records.iter().flat_map(|record| &record.char_timings) {
+            for typed in timing.mistyped.chars() {
+                *counts.entry((timing.ch, typed)).or_default() += 1;
+            }
+        }
+
+        let mut confusions: Vec<Confusion> = counts
+            .into_iter()
+            .map(|((expected, typed), count)| Confusion {
+                expected,
+                typed,
+                count,
+                expected_code: array30_data::get_array30_code(expected),
+                typed_code: array30_data::get_array30_code(typed),
+            })
+            .collect();
+        confusions.sort_by_key(|confusion| std::cmp::Reverse(confusion.count));
+
+        ConfusionReport { confusions }
+    }
+
+    /// A practice text that alternates each of the top confusions with its look-alike,
+    /// always ending on the expected character.
+    pub fn drill_text(&self, limit: usize) -> Option<String> {
+        let text: String = self
+            .confusions
+            .iter()
+            .take(limit)
+            .flat_map(|c| [c.expected, c.typed, c.expected, c.typed, c.expected])
+            .collect();
+        (!text.is_empty()).then_some(text)
+    }
+}
+
+/// Sessions and active practice time within one calendar period.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PeriodTotals {
+    pub sessions: usize,
+    pub practice_ms: u64,
+}
+
+impl PeriodTotals {
+    pub fn minutes(&self) -> f64 {
+        self.practice_ms as f64 / 60_000.0
+    }
+
+    fn add(&mut self, record: &SessionRecord) {
+        self.sessions += 1;
+        self.practice_ms += record.elapsed_ms;
+    }
+}
+
+/// Practice grouped into calendar days and weeks on the learner's chosen clock.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PracticeCalendar {
+    pub days: BTreeMap<NaiveDate, PeriodTotals>,
+    /// Keyed by the Monday each week starts on.
+    pub weeks: BTreeMap<NaiveDate, PeriodTotals>,
+}
+
+impl PracticeCalendar {
+    pub fn from_records(records: &[SessionRecord], time_zone: TimeZoneChoice) -> Self {
+        let mut calendar = PracticeCalendar::default();
+        for record in records {
+            let day = time_zone.day(record);
+            calendar.days.entry(day).or_default().add(record);
+            calendar
+                .weeks
+                .entry(week_start(day))
+                .or_default()
+                .add(record);
+        }
+        calendar
+    }
+
+    pub fn day(&self, day: NaiveDate) -> PeriodTotals {
+        self.days.get(&day).copied().unwrap_or_default()
+    }
+
+    /// Totals of the week `day` falls in.
+    pub fn week_of(&self, day: NaiveDate) -> PeriodTotals {
+        self.weeks
+            .get(&week_start(day))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Runs of consecutive days with at least `goal_ms` of practice. A streak
+    /// still counts as current while today is short of the goal, as long as
+    /// yesterday met it.
+    pub fn streaks(&self, goal_ms: u64, today: NaiveDate) -> Streaks {
+        let met = |day: NaiveDate| self.day(day).practice_ms >= goal_ms.max(1);
+
+        let mut longest = 0;
+        let mut run = 0;
+        let mut previous: Option<NaiveDate> = None;
+        for day in self.days.keys().copied().filter(|day| met(*day)) {
+            run = match previous {
+                Some(previous) if previous.succ_opt() == Some(day) => run + 1,
+                _ => 1,
+            };
+            longest = longest.max(run);
+            previous = Some(day);
+        }
+
+        let mut day = if met(today) {
+            today
+        } else {
+            today - Days::new(1)
+        };
+        let mut current = 0;
+        while met(day) {
+            current += 1;
+            day = day - Days::new(1);
+        }
+
+        Streaks { current, longest }
+    }
+}
+
+/// Consecutive days on which the daily goal was met.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Streaks {
+    pub current: usize,
+    pub longest: usize,
+}
+
+/// Sessions averaged into each point of a progress chart's trend line.
+pub const MOVING_AVERAGE_SESSIONS: usize = 5;
+
+/// How far back the progress charts look.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChartRange {
+    Week,
+    #[default]
+    Month,
+    All,
+}
+
+impl ChartRange {
+    pub const ALL: [ChartRange; 3] = [ChartRange::Week, ChartRange::Month, ChartRange::All];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ChartRange::Week => "7 days",
+            ChartRange::Month => "30 days",
+            ChartRange::All => "All",
+        }
+    }
+
+    /// The earliest session shown, or `None` for the whole history.
+    pub fn start(self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            ChartRange::Week => Some(now - Duration::days(7)),
+            ChartRange::Month => Some(now - Duration::days(30)),
+            ChartRange::All => None,
+        }
+    }
+}
+
+/// One session on a progress chart.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChartPoint {
+    pub recorded_at: DateTime<Utc>,
+    /// The day it was recorded on the learner's chosen clock.
+    pub day: NaiveDate,
+    pub value: f64,
+    /// Mean over this session and the ones before it, up to
+    /// [`MOVING_AVERAGE_SESSIONS`]. Sessions before the range still count, so
+    /// the line does not restart at its left edge.
+    pub average: f64,
+}
+
+/// `value` of each session recorded since `start` and not rejected by `rules`,
+/// oldest first, with its moving average. `records` must be oldest first, as
+/// history loads them.
+pub fn progress_points(
+    records: &[SessionRecord],
+    rules: &OutlierRules,
+    start: Option<DateTime<Utc>>,
+    time_zone: TimeZoneChoice,
+    value: impl Fn(&SessionRecord) -> f64,
+) -> Vec<ChartPoint> {
+    let kept: Vec<&SessionRecord> = records
+        .iter()
+        .filter(|record| rules.rejects(record).is_none())
+        .collect();
+    let values: Vec<f64> = kept.iter().map(|record| value(record)).collect();
+    kept.into_iter()
+        .enumerate()
+        .filter(|(_, record)| start.is_none_or(|start| record.recorded_at >= start))
+        .map(|(index, record)| {
+            let window = &values[(index + 1).saturating_sub(MOVING_AVERAGE_SESSIONS)..=index];
+            ChartPoint {
+                recorded_at: record.recorded_at,
+                day: time_zone.day(record),
+                value: values[index],
+                average: window.iter().sum::<f64>() / window.len() as f64,
+            }
+        })
+        .collect()
+}
+
+/// What groups a session's attempts together: the exercise text for now, until
+/// exercises carry an ID of their own.
+pub fn exercise_key(record: &SessionRecord) -> &str {
+    &record.exercise_text
+}
+
+/// Every attempt at one exercise.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExerciseStats {
+    pub exercise: String,
+    pub attempts: usize,
+    pub best_wpm: f64,
+    pub average_wpm: f64,
+    pub average_accuracy: f64,
+    pub last_attempt: DateTime<Utc>,
+}
+
+/// Sessions grouped by exercise, so harder sentences are not averaged in with
+/// easier ones. Sessions rejected by the [`OutlierRules`] are left out, so a
+/// pasted run cannot stand as a best.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExerciseReport {
+    /// Most attempted first; ties go to the most recently practised.
+    pub exercises: Vec<ExerciseStats>,
+}
+
+impl ExerciseReport {
+    pub fn from_records(records: &[SessionRecord], rules: &OutlierRules) -> Self {
+        let mut groups: BTreeMap<&str, Vec<&SessionRecord>> = BTreeMap::new();
+        for record in records
+            .iter()
+            .filter(|record| rules.rejects(record).is_none())
+        {
+            groups.entry(exercise_key(record)).or_default().push(record);
+        }
+
+        let mut exercises: Vec<ExerciseStats> = groups
+            .into_iter()
+            .map(|(exercise, attempts)| {
+                let count = attempts.len() as f64;
+                ExerciseStats {
+                    exercise: exercise.to_string(),
+                    attempts: attempts.len(),
+                    best_wpm: attempts.iter().map(|record| record.wpm).fold(0.0, f64::max),
+                    average_wpm: attempts.iter().map(|record| record.wpm).sum::<f64>() / count,
+                    average_accuracy: attempts.iter().map(|record| record.accuracy).sum::<f64>()
+                        / count,
+                    last_attempt: attempts
+                        .iter()
+                        .map(|record| record.recorded_at)
+                        .max()
+                        .unwrap_or_default(),
+                }
+            })
+            .collect();
+        exercises.sort_by(|a, b| {
+            b.attempts
+                .cmp(&a.attempts)
+                .then(b.last_attempt.cmp(&a.last_attempt))
+        });
+        ExerciseReport { exercises }
+    }
+}
+
+/// Sessions needed before a trend is fitted.
+pub const MIN_TREND_SESSIONS: usize = 5;
+
+/// Days over which a projection is still worth stating.
+const MAX_PROJECTION_DAYS: f64 = 3_650.0;
+
+/// A least-squares fit of `value = intercept + slope × ln(1 + days)`, days
+/// counted from the first session: quick early gains that level off, as
+/// learning curves do.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Trend {
+    pub start: DateTime<Utc>,
+    pub intercept: f64,
+    pub slope: f64,
+}
+
+impl Trend {
+    /// `None` with fewer than [`MIN_TREND_SESSIONS`] samples, or when they all
+    /// fall at the same moment.
+    pub fn fit(samples: &[(DateTime<Utc>, f64)]) -> Option<Self> {
+        if samples.len() < MIN_TREND_SESSIONS {
+            return None;
+        }
+        let start = samples.iter().map(|(at, _)| *at).min()?;
+        let points: Vec<(f64, f64)> = samples
+            .iter()
+            .map(|(at, value)| (log_days(start, *at), *value))
+            .collect();
+        let count = points.len() as f64;
+        let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / count;
+        let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / count;
+        let spread_x: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+        if spread_x < 1e-12 {
+            return None;
+        }
+        let slope = points
+            .iter()
+            .map(|(x, y)| (x - mean_x) * (y - mean_y))
+            .sum::<f64>()
+            / spread_x;
+        Some(Trend {
+            start,
+            intercept: mean_y - slope * mean_x,
+            slope,
+        })
+    }
+
+    /// The fitted value at `at`.
+    pub fn at(&self, at: DateTime<Utc>) -> f64 {
+        self.intercept + self.slope * log_days(self.start, at)
+    }
+
+    /// When the fitted curve reaches `goal`: `None` if it is flat or falling,
+    /// or would take more than ten years from the first session.
+    pub fn reaches(&self, goal: f64) -> Option<DateTime<Utc>> {
+        if self.slope <= 0.0 {
+            return None;
+        }
+        let days = ((goal - self.intercept) / self.slope).exp() - 1.0;
+        (days <= MAX_PROJECTION_DAYS)
+            .then(|| self.start + Duration::milliseconds((days.max(0.0) * 86_400_000.0) as i64))
+    }
+}
+
+/// `ln(1 + days)` from `start` to `at`.
+fn log_days(start: DateTime<Utc>, at: DateTime<Utc>) -> f64 {
+    let days = (at - start).num_milliseconds() as f64 / 86_400_000.0;
+    days.max(0.0).ln_1p()
+}
+
+/// A column of the session history table.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SessionColumn {
+    #[default]
+    When,
+    Exercise,
+    Mode,
+    Speed,
+    Accuracy,
+    Duration,
+}
+
+impl SessionColumn {
+    pub const ALL: [SessionColumn; 6] = [
+        SessionColumn::When,
+        SessionColumn::Exercise,
+        SessionColumn::Mode,
+        SessionColumn::Speed,
+        SessionColumn::Accuracy,
+        SessionColumn::Duration,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SessionColumn::When => "When",
+            SessionColumn::Exercise => "Exercise",
+            SessionColumn::Mode => "Mode",
+            SessionColumn::Speed => "Speed",
+            SessionColumn::Accuracy => "Accuracy",
+            SessionColumn::Duration => "Duration",
+        }
+    }
+
+    fn compare(self, a: &SessionRecord, b: &SessionRecord) -> Ordering {
+        match self {
+            SessionColumn::When => a.recorded_at.cmp(&b.recorded_at),
+            SessionColumn::Exercise => a.exercise_text.cmp(&b.exercise_text),
+            SessionColumn::Mode => a.mode.to_string().cmp(&b.mode.to_string()),
+            SessionColumn::Speed => a.wpm.total_cmp(&b.wpm),
+            SessionColumn::Accuracy => a.accuracy.total_cmp(&b.accuracy),
+            SessionColumn::Duration => a.elapsed_ms.cmp(&b.elapsed_ms),
+        }
+    }
+}
+
+/// Which sessions the history table lists, and in what order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SessionQuery {
+    pub sort: SessionColumn,
+    pub descending: bool,
+    /// First day listed, on the learner's chosen clock.
+    pub from: Option<NaiveDate>,
+    /// Last day listed, inclusive.
+    pub to: Option<NaiveDate>,
+    /// Only exercises containing this text; empty lists all.
+    pub exercise: String,
+    pub mode: Option<PracticeMode>,
+}
+
+impl Default for SessionQuery {
+    fn default() -> Self {
+        SessionQuery {
+            sort: SessionColumn::When,
+            descending: true,
+            from: None,
+            to: None,
+            exercise: String::new(),
+            mode: None,
+        }
+    }
+}
+
+impl SessionQuery {
+    /// Sorts by `column`, flipping the order if it is already the sort column.
+    /// Figures and dates start highest first, text A to Z.
+    pub fn sort_by(&mut self, column: SessionColumn) {
+        if self.sort == column {
+            self.descending = !self.descending;
+        } else {
+            self.sort = column;
+            self.descending = !matches!(column, SessionColumn::Exercise | SessionColumn::Mode);
+        }
+    }
+
+    pub fn matches(&self, record: &SessionRecord, time_zone: TimeZoneChoice) -> bool {
+        let day = time_zone.day(record);
+        self.from.is_none_or(|from| day >= from)
+            && self.to.is_none_or(|to| day <= to)
+            && self.mode.is_none_or(|mode| record.mode == mode)
+            && record.exercise_text.contains(self.exercise.trim())
+    }
+
+    /// The matching sessions in order; ties keep the newest first.
+    pub fn apply<'a>(
+        &self,
+        records: &'a [SessionRecord],
+        time_zone: TimeZoneChoice,
+    ) -> Vec<&'a SessionRecord> {
+        let mut matching: Vec<&SessionRecord> = records
+            .iter()
+            .rev()
+            .filter(|record| self.matches(record, time_zone))
+            .collect();
+        matching.sort_by(|a, b| {
+            let order = self.sort.compare(a, b);
+            if self.descending {
+                order.reverse()
+            } else {
+                order
+            }
+        });
+        matching
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        array30_key_label, finger_for_key, progress_points, CharacterReport, ChartRange,
+        ConfusionReport, ExerciseReport, KeyReport, PeriodTotals, PracticeCalendar, SessionColumn,
+        SessionQuery, Streaks, Trend,
+    };
+    use crate::logic::{CharTiming, PracticeMode};
+    use crate::robust_stats::OutlierRules;
+    use crate::storage::SessionRecord;
+    use crate::time_zone::TimeZoneChoice;
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    fn timing(ch: char, latency_ms: Option<u64>, errors: usize) -> CharTiming {
+        CharTiming {
+            ch,
+            latency_ms,
+            errors,
+            ..CharTiming::default()
+        }
+    }
+
+    #[test]
+    fn report_aggregates_characters_and_shared_codes() {
+        let records = vec![
+            SessionRecord {
+                char_timings: vec![timing('己', Some(900), 1), timing('已', Some(300), 0)],
+                ..SessionRecord::default()
+            },
+            SessionRecord {
+                char_timings: vec![timing('己', Some(500), 2), timing('已', None, 1)],
+                ..SessionRecord::default()
+            },
+        ];
+
+        let report = CharacterReport::from_records(&records);
+
+        let slowest = report.slowest(1);
+        assert_eq!(slowest[0].ch, '己');
+        assert_eq!(slowest[0].mean_latency_ms(), Some(700.0));
+        assert_eq!(slowest[0].code, Some("ts"));
+
+        let missed = report.most_error_prone(2);
+        assert_eq!(missed[0].errors, 3);
+        assert_eq!(missed[1].ch, '已');
+        assert_eq!(missed[1].attempts, 2);
+        assert_eq!(missed[1].timed, 1);
+
+        assert_eq!(report.codes.len(), 1);
+        assert_eq!(report.codes[0].characters, vec!['己', '已']);
+        assert_eq!(report.codes[0].errors, 4);
+        assert_eq!(report.codes[0].mean_latency_ms(), Some(1700.0 / 3.0));
+    }
+
+    #[test]
+    fn key_report_blames_expected_code_and_marks_typed_code() {
+        // 己 is "ts"; 的 is typed instead once.
+        let typed_instead = crate::array30_data::get_array30_code('的').unwrap();
+        let records = vec![SessionRecord {
+            char_timings: vec![CharTiming {
+                ch: '己',
+                latency_ms: Some(800),
+                errors: 1,
+                mistyped: "的".to_string(),
+            }],
+            ..SessionRecord::default()
+        }];
+
+        let report = KeyReport::from_records(&records);
+
+        assert_eq!(report.key('t').blamed_errors, 1);
+        assert_eq!(report.key('s').mean_latency_ms(), Some(400.0));
+        for key in typed_instead.split('|').next().unwrap().chars() {
+            assert!(report.key(key).pressed_instead >= 1);
+        }
+        assert_eq!(array30_key_label('q').as_deref(), Some("1↑"));
+        assert_eq!(array30_key_label(';').as_deref(), Some("0-"));
+        assert_eq!(finger_for_key('g'), Some(3));
+        assert_eq!(finger_for_key('/'), Some(7));
+    }
+
+    #[test]
+    fn confusions_are_counted_and_compared_by_code() {
+        let records = vec![
+            SessionRecord {
+                char_timings: vec![CharTiming {
+                    ch: '已',
+                    errors: 2,
+                    mistyped: "己己".to_string(),
+                    ..CharTiming::default()
+                }],
+                ..SessionRecord::default()
+            },
+            SessionRecord {
+                char_timings: vec![CharTiming {
+                    ch: '的',
+                    errors: 1,
+                    mistyped: "是".to_string(),
+                    ..CharTiming::default()
+                }],
+                ..SessionRecord::default()
+            },
+        ];
+
+        let report = ConfusionReport::from_records(&records);
+
+        let top = &report.confusions[0];
+        assert_eq!((top.expected, top.typed, top.count), ('已', '己', 2));
+        assert_eq!(top.expected_code, top.typed_code);
+        assert!(top.differing_keys().is_empty());
+        assert!(!report.confusions[1].differing_keys().is_empty());
+        assert_eq!(report.drill_text(1).as_deref(), Some("已己已己已"));
+        assert_eq!(ConfusionReport::default().drill_text(3), None);
+    }
+
+    #[test]
+    fn calendar_groups_sessions_by_day_and_week_in_the_chosen_zone() {
+        let at = |day, hour, elapsed_ms| SessionRecord {
+            recorded_at: Utc.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap(),
+            utc_offset_minutes: 480,
+            elapsed_ms,
+            ..SessionRecord::default()
+        };
+        // Sunday evening and Monday morning in UTC; both Monday in Taipei.
+        let records = [at(1, 20, 1_000), at(2, 1, 2_000), at(4, 1, 4_000)];
+        let date = |day| NaiveDate::from_ymd_opt(2026, 3, day).unwrap();
+
+        let utc = PracticeCalendar::from_records(&records, TimeZoneChoice::Fixed(0));
+        let taipei = PracticeCalendar::from_records(&records, TimeZoneChoice::AsRecorded);
+
+        assert_eq!(utc.day(date(2)).sessions, 1);
+        assert_eq!(utc.week_of(date(4)).practice_ms, 6_000);
+        assert_eq!(
+            taipei.day(date(2)),
+            PeriodTotals {
+                sessions: 2,
+                practice_ms: 3_000
+            }
+        );
+        assert_eq!(taipei.week_of(date(8)).sessions, 3);
+        assert_eq!(taipei.day(date(3)), PeriodTotals::default());
+    }
+
+    #[test]
+    fn streaks_count_days_that_meet_the_goal() {
+        let minutes = |day, minutes: u64| SessionRecord {
+            recorded_at: Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap(),
+            elapsed_ms: minutes * 60_000,
+            ..SessionRecord::default()
+        };
+        // Three days in a row, a short day, then two days ending yesterday.
+        let records = [
+            minutes(1, 6),
+            minutes(2, 5),
+            minutes(3, 9),
+            minutes(4, 2),
+            minutes(5, 5),
+            minutes(6, 7),
+        ];
+        let calendar = PracticeCalendar::from_records(&records, TimeZoneChoice::Fixed(0));
+        let date = |day| NaiveDate::from_ymd_opt(2026, 3, day).unwrap();
+
+        assert_eq!(
+            calendar.streaks(5 * 60_000, date(7)),
+            Streaks {
+                current: 2,
+                longest: 3
+            }
+        );
+        assert_eq!(calendar.streaks(5 * 60_000, date(8)).current, 0);
+        assert_eq!(calendar.streaks(60_000, date(6)).current, 6);
+        assert_eq!(calendar.day(date(3)).minutes(), 9.0);
+    }
+
+    #[test]
+    fn progress_points_average_across_the_range_start() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 31, 12, 0, 0).unwrap();
+        let mut records: Vec<SessionRecord> = (0..8)
+            .map(|n| SessionRecord {
+                recorded_at: now - chrono::Duration::days(2 * (7 - n)),
+                wpm: 10.0 * (n + 1) as f64,
+                exercise_text: "天地玄黃".to_string(),
+                ..SessionRecord::default()
+            })
+            .collect();
+        // A pasted run in the middle moves neither the points nor the average.
+        records.insert(
+            4,
+            SessionRecord {
+                recorded_at: now - chrono::Duration::days(7),
+                wpm: 900.0,
+                exercise_text: "天地玄黃".to_string(),
+                ..SessionRecord::default()
+            },
+        );
+        let zone = TimeZoneChoice::Fixed(0);
+        let rules = OutlierRules::default();
+
+        let all = progress_points(&records, &rules, ChartRange::All.start(now), zone, |r| {
+            r.wpm
+        });
+        assert_eq!(all.len(), 8);
+        assert_eq!(all[0].average, 10.0);
+        assert_eq!(all[1].average, 15.0);
+
+        let week = progress_points(&records, &rules, ChartRange::Week.start(now), zone, |r| {
+            r.wpm
+        });
+        assert_eq!(week.len(), 4);
+        assert_eq!(week[0].value, 50.0);
+        assert_eq!(week[0].average, 30.0);
+        assert_eq!(week[3].average, 60.0);
+        assert_eq!(week[3].day, now.date_naive());
+    }
+
+    #[test]
+    fn session_query_filters_and_sorts() {
+        let session = |day, text: &str, mode, wpm| SessionRecord {
+            recorded_at: Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap(),
+            exercise_text: text.to_string(),
+            mode,
+            wpm,
+            ..SessionRecord::default()
+        };
+        let records = [
+            session(1, "天地", PracticeMode::Lesson, 30.0),
+            session(2, "已己", PracticeMode::Drill, 50.0),
+            session(3, "天空", PracticeMode::Lesson, 40.0),
+            session(4, "天天", PracticeMode::Lesson, 40.0),
+        ];
+        let zone = TimeZoneChoice::Fixed(0);
+        let texts = |query: &SessionQuery| -> Vec<String> {
+            query
+                .apply(&records, zone)
+                .into_iter()
+                .map(|record| record.exercise_text.clone())
+                .collect()
+        };
+
+        let mut query = SessionQuery::default();
+        assert_eq!(texts(&query), ["天天", "天空", "已己", "天地"]);
+
+        query.sort_by(SessionColumn::Speed);
+        assert_eq!(texts(&query), ["已己", "天天", "天空", "天地"]);
+        query.sort_by(SessionColumn::Speed);
+        assert_eq!(texts(&query), ["天地", "天天", "天空", "已己"]);
+
+        query.exercise = "天".to_string();
+        query.mode = Some(PracticeMode::Lesson);
+        query.from = NaiveDate::from_ymd_opt(2026, 3, 2);
+        query.to = NaiveDate::from_ymd_opt(2026, 3, 3);
+        assert_eq!(texts(&query), ["天空"]);
+    }
+
+    #[test]
+    fn exercises_keep_their_own_bests_and_averages() {
+        let attempt = |text: &str, wpm, accuracy| SessionRecord {
+            exercise_text: text.to_string(),
+            wpm,
+            accuracy,
+            ..SessionRecord::default()
+        };
+        let records = [
+            attempt("天地", 20.0, 90.0),
+            attempt("已己", 50.0, 100.0),
+            attempt("天地", 30.0, 100.0),
+            // Pasted, so neither a best nor part of the averages.
+            attempt("天地", 900.0, 100.0),
+        ];
+        let rules = OutlierRules {
+            min_characters: 2,
+            ..OutlierRules::default()
+        };
+
+        let report = ExerciseReport::from_records(&records, &rules);
+        let hard = &report.exercises[0];
+        assert_eq!(hard.exercise, "天地");
+        assert_eq!(hard.attempts, 2);
+        assert_eq!(hard.best_wpm, 30.0);
+        assert_eq!(hard.average_wpm, 25.0);
+        assert_eq!(hard.average_accuracy, 95.0);
+        assert_eq!(report.exercises[1].best_wpm, 50.0);
+    }
+
+    #[test]
+    fn trend_fits_log_time_and_projects_a_goal() {
+        let start = Utc.with_ymd_and_hms(2026, 1, 1, 9, 0, 0).unwrap();
+        let samples: Vec<_> = [0, 1, 3, 7, 15, 31]
+            .into_iter()
+            .map(|days: i64| {
+                let at = start + chrono::Duration::days(days);
+                (at, 10.0 + 5.0 * (days as f64).ln_1p())
+            })
+            .collect();
+
+        let trend = Trend::fit(&samples).unwrap();
+        assert!((trend.intercept - 10.0).abs() < 1e-9);
+        assert!((trend.slope - 5.0).abs() < 1e-9);
+
+        let reached = trend.reaches(10.0 + 5.0 * 64f64.ln()).unwrap();
+        assert!(
+            (reached - (start + chrono::Duration::days(63)))
+                .num_seconds()
+                .abs()
+                < 60
+        );
+        assert_eq!(trend.reaches(200.0), None);
+        assert_eq!(Trend::fit(&samples[..4]), None);
+    }
+}
@@ -3,19 +3,33 @@ use crate::components::alert_dialog::{
     AlertDialogDescription, AlertDialogRoot, AlertDialogTitle,
 };
 use crate::components::button::{Button, ButtonVariant};
-use crate::components::separator::Separator;
 use crate::components::tabs::{TabContent, TabList, TabTrigger, Tabs};
 use crate::components::toast::ToastProvider;
-use crate::components::{PracticeInterface, StatisticsDisplay};
-use crate::logic::PracticeSession;
-use crate::storage::HistoryManager;
+use crate::components::{
+    sync_history, CharacterInsights, ConfusionView, ExerciseLeaderboard, GoalProjection,
+    HistoryTransfer, KeyboardHeatmap, PracticeHeatmap, PracticeInterface, ProfileSwitcher,
+    ProgressCharts, ResumePrompt, ServerSync, SessionHistory, SessionReplay, SettingsPanel,
+    StatisticsDisplay, TrashBin, UnreadableSessions,
+};
+use crate::logic::{PracticeSession, SessionSnapshot};
+use crate::settings::Settings;
+use crate::storage::{
+    listen_for_changes, BrowserBackend, History, MigrationReport, Profiles, StorageNotice,
+    ACTIVE_SESSION_KEY, PROGRESS_KEY, TRASH_RETENTION_DAYS,
+};
 use dioxus::prelude::*;
 use dioxus_primitives::toast::{use_toast, ToastOptions};
+use futures_util::StreamExt;
+use gloo_timers::future::TimeoutFuture;
+
+/// How often the exercise in progress is saved for resuming.
+const SNAPSHOT_INTERVAL_MS: u32 = 5_000;
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum AppTab {
     Practice,
     Statistics,
+    Settings,
 }
 
 impl std::fmt::Display for AppTab {
@@ -23,6 +37,28 @@ impl std::fmt::Display for AppTab {
         match self {
             AppTab::Practice => write!(f, "practice"),
             AppTab::Statistics => write!(f, "statistics"),
+            AppTab::Settings => write!(f, "settings"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum StatsView {
+    Overview,
+    Characters,
+    Keyboard,
+    Confusions,
+    Exercises,
+}
+
+impl std::fmt::Display for StatsView {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            StatsView::Overview => write!(f, "overview"),
+            StatsView::Characters => write!(f, "characters"),
+            StatsView::Keyboard => write!(f, "keyboard"),
+            StatsView::Confusions => write!(f, "confusions"),
+            StatsView::Exercises => write!(f, "exercises"),
         }
     }
 }
@@ -40,12 +76,152 @@ pub fn app() -> Element {
 fn AppContent() -> Element {
     let toast_api = use_toast();
     let mut current_tab = use_signal(|| Some(AppTab::Practice.to_string()));
+    let stats_view = use_signal(|| Some(StatsView::Overview.to_string()));
     let mut show_reset_dialog = use_signal(|| false);
-    let session = use_signal(PracticeSession::new);
+    let mut session = use_signal(PracticeSession::new);
+    let mut profiles =
+        use_context_provider(|| Signal::new(Profiles::new(BrowserBackend::default())));
+    let mut history = use_context_provider(|| Signal::new(profiles.peek().history()));
+    let mut settings = use_context_provider(|| Signal::new(Settings::default()));
+    let mut migration_report = use_signal(MigrationReport::default);
+    // Nothing is saved until a profile was loaded, so defaults never overwrite it.
+    let mut loaded = use_signal(|| false);
+    let mut pending_resume = use_signal(|| None::<SessionSnapshot>);
+    // Events and pause state of the last saved snapshot, to skip unchanged saves.
+    let mut snapshot_marker = use_signal(|| None::<(usize, bool)>);
+
+    // Loads the active profile's history, settings and curriculum position.
+    let mut activate_profile = move || {
+        let active = profiles.peek().history();
+        let report = active.migrate();
+        active.purge_trash(chrono::Utc::now());
+        if !report.failed.is_empty() {
+            toast_api.warning(
+                "Some saved sessions could not be read".to_string(),
+                ToastOptions::new().description(format!(
+                    "{} sessions were kept as they are; see Statistics for details.",
+                    report.failed.len()
+                )),
+            );
+        }
+        let saved_settings = Settings::load(&active);
+        session.set(PracticeSession::restore(
+            active.load_value(PROGRESS_KEY).unwrap_or_default(),
+            saved_settings.idle_threshold_ms,
+        ));
+        settings.set(saved_settings);
+        pending_resume.set(active.load_value(ACTIVE_SESSION_KEY));
+        snapshot_marker.set(None);
+        migration_report.set(report);
+        history.set(active);
+        loaded.set(true);
+        // Brings in what other devices recorded while this one was away.
+        spawn(async move {
+            if let Some(Err(error)) = sync_history(history).await {
+                toast_api.warning(
+                    "Could not sync with the server".to_string(),
+                    ToastOptions::new().description(error.to_string()),
+                );
+            }
+        });
+    };
+    use_future(move || async move {
+        let store = Profiles::new(BrowserBackend::open().await);
+        if let Err(error) = store.ensure_default() {
+            toast_api.error(
+                "Could not set up learner profiles".to_string(),
+                ToastOptions::new().description(error.to_string()),
+            );
+        }
+        profiles.set(store);
+        activate_profile();
+    });
+
+    // Sessions recorded or deleted in another tab show up here without a reload.
+    let remote_changes = use_coroutine(
+        move |mut notices: UnboundedReceiver<StorageNotice>| async move {
+            while let Some(notice) = notices.next().await {
+                let active = history.peek().clone();
+                let Some(change) = active.backend().remote_change(notice).await else {
+                    continue;
+                };
+                if active.apply_remote(&change) {
+                    history.set(active);
+                } else if Profiles::is_profile_list(&change.key) {
+                    let store = profiles.peek().clone();
+                    profiles.set(store);
+                }
+            }
+        },
+    );
+    use_hook(move || {
+        let notices = remote_changes.tx();
+        listen_for_changes(move |notice| {
+            let _ = notices.unbounded_send(notice);
+        });
+    });
+
+    // Keeps the exercise in progress saved, so closing the page does not lose it.
+    use_future(move || async move {
+        loop {
+            TimeoutFuture::new(SNAPSHOT_INTERVAL_MS).await;
+            if !*loaded.peek() {
+                continue;
+            }
+            let session = session.peek();
+            let Some(snapshot) = session.snapshot(now_ms()) else {
+                continue;
+            };
+            let marker = Some((snapshot.events.len(), session.is_paused()));
+            if marker == *snapshot_marker.peek() {
+                continue;
+            }
+            match history.peek().save_value(ACTIVE_SESSION_KEY, &snapshot) {
+                Ok(()) => snapshot_marker.set(marker),
+                Err(error) => web_sys::console::warn_1(
+                    &format!("Could not save the exercise in progress: {error}").into(),
+                ),
+            }
+        }
+    });
+    // Finished or reset: nothing left to resume. A pending offer keeps its snapshot.
+    let started = use_memo(move || session.read().started);
+    use_effect(move || {
+        if started() || !*loaded.peek() || pending_resume.peek().is_some() {
+            return;
+        }
+        history.peek().remove_value(ACTIVE_SESSION_KEY);
+        snapshot_marker.set(None);
+    });
+
+    // Saved only when the lesson changes, not on every keystroke.
+    let progress = use_memo(move || session.read().progress());
+    use_effect(move || {
+        let progress = progress();
+        if !*loaded.peek() {
+            return;
+        }
+        if let Err(error) = history.peek().save_value(PROGRESS_KEY, &progress) {
+            web_sys::console::warn_1(&format!("Could not save progress: {error}").into());
+        }
+    });
+    use_effect(move || {
+        let settings = settings();
+        if session.peek().idle_threshold_ms != settings.idle_threshold_ms {
+            session.write().idle_threshold_ms = settings.idle_threshold_ms;
+        }
+        if !*loaded.peek() {
+            return;
+        }
+        if let Err(error) = settings.save(&history.peek()) {
+            web_sys::console::warn_1(&format!("Could not save settings: {error}").into());
+        }
+    });
     let stats = session.read().stats.clone();
-    let wpm = stats.wpm();
+    let speed_metric = settings.read().speed_metric;
+    let speed = speed_metric.convert(stats.wpm());
     let accuracy = stats.accuracy();
-    let wpm_text = format!("{wpm:.0}");
+    let speed_text = format!("{speed:.0}");
     let accuracy_text = format!("{accuracy:.0}%");
 
     rsx! {
@@ -71,6 +247,7 @@ fn AppContent() -> Element {
                                 p { class: "text-xs font-bold uppercase tracking-[0.3em] text-primary/70", "Lesson 4" }
                                 h1 { class: "text-4xl md:text-5xl font-extrabold tracking-tight", "Array30 Practice" }
                                 p { class: "text-base text-base-content/70", "Master the art of typing with playful momentum." }
+                                ProfileSwitcher { on_change: move |_| activate_profile() }
                             }
                             TabList {
                                 class: "tabs-boxed w-fit bg-white/80 shadow-md p-1 rounded-full",
@@ -86,6 +263,12 @@ fn AppContent() -> Element {
                                     value: AppTab::Statistics.to_string(),
                                     "Statistics"
                                 }
+                                TabTrigger {
+                                    class: "tab px-6 py-2 font-semibold",
+                                    index: 2usize,
+                                    value: AppTab::Settings.to_string(),
+                                    "Settings"
+                                }
                             }
                         }
 
@@ -94,8 +277,8 @@ fn AppContent() -> Element {
                             class: "grid gap-4 md:grid-cols-3",
                             div {
                                 class: "bg-white rounded-2xl shadow-sm border-b-4 border-primary/30 p-4 text-center",
-                                p { class: "text-xs font-bold uppercase text-primary/80", "WPM" }
-                                p { class: "text-3xl font-black text-base-content", "{wpm_text}" }
+                                p { class: "text-xs font-bold uppercase text-primary/80", "{speed_metric.unit()}" }
+                                p { class: "text-3xl font-black text-base-content", "{speed_text}" }
                             }
                             div {
                                 class: "bg-white rounded-2xl shadow-sm border-b-4 border-accent/60 p-4 text-center",
@@ -113,6 +296,22 @@ fn AppContent() -> Element {
                             class: "app-tab-content mt-2",
                             index: 0usize,
                             value: AppTab::Practice.to_string(),
+                            if let Some(snapshot) = pending_resume() {
+                                div {
+                                    class: "mb-6",
+                                    ResumePrompt {
+                                        snapshot: snapshot.clone(),
+                                        on_resume: move |_| {
+                                            session.write().continue_from(snapshot.clone(), now_ms());
+                                            pending_resume.set(None);
+                                        },
+                                        on_discard: move |_| {
+                                            history.peek().remove_value(ACTIVE_SESSION_KEY);
+                                            pending_resume.set(None);
+                                        }
+                                    }
+                                }
+                            }
                             PracticeInterface { session: session }
                         }
 
@@ -120,27 +319,23 @@ fn AppContent() -> Element {
                             class: "app-tab-content mt-2",
                             index: 1usize,
                             value: AppTab::Statistics.to_string(),
-                            div {
-                                class: "space-y-6",
-                                StatisticsDisplay {
-                                    stats: HistoryManager::get_statistics()
-                                }
-
-                                div {
-                                    class: "flex flex-wrap gap-3",
-                                    Button {
-                                        variant: ButtonVariant::Ghost,
-                                        onclick: move |_| current_tab.set(Some(AppTab::Practice.to_string())),
-                                        "Back to Practice"
-                                    }
-                                    Button {
-                                        variant: ButtonVariant::Primary,
-                                        onclick: move |_| show_reset_dialog.set(true),
-                                        "Reset All Progress"
-                                    }
+                            if current_tab() == Some(AppTab::Statistics.to_string()) {
+                                StatisticsTab {
+                                    stats_view,
+                                    session,
+                                    current_tab,
+                                    show_reset_dialog,
+                                    migration_report,
                                 }
                             }
                         }
+
+                        TabContent {
+                            class: "app-tab-content mt-2",
+                            index: 2usize,
+                            value: AppTab::Settings.to_string(),
+                            SettingsPanel {}
+                        }
                     }
 
                     AlertDialogRoot {
@@ -150,18 +345,25 @@ fn AppContent() -> Element {
                             AlertDialogTitle { class: "text-lg font-semibold", "Reset all progress?" }
                             AlertDialogDescription {
                                 class: "text-sm text-base-content/70",
-                                "This will permanently remove all saved practice sessions and statistics."
+                                "All saved practice sessions and statistics of the current learner move to the trash, where they can be restored for {TRASH_RETENTION_DAYS} days."
                             }
                             AlertDialogActions {
                                 AlertDialogCancel { "Cancel" }
                                 AlertDialogAction {
                                     on_click: move |_| {
-                                        HistoryManager::clear_history();
-                                        current_tab.set(Some(AppTab::Practice.to_string()));
-                                        toast_api.warning(
-                                            "All progress has been reset.".to_string(),
-                                            ToastOptions::new().description("Your local history was cleared.")
-                                        );
+                                        match history.write().reset_history(chrono::Utc::now()) {
+                                            Ok(()) => {
+                                                current_tab.set(Some(AppTab::Practice.to_string()));
+                                                toast_api.warning(
+                                                    "All progress has been reset.".to_string(),
+                                                    ToastOptions::new().description("Restore it from the trash under Statistics if this was a mistake.")
+                                                );
+                                            }
+                                            Err(error) => toast_api.error(
+                                                "Progress was not reset".to_string(),
+                                                ToastOptions::new().description(error.to_string())
+                                            ),
+                                        }
                                         show_reset_dialog.set(false);
                                     },
                                     "Reset Everything"
@@ -174,3 +376,150 @@ fn AppContent() -> Element {
         }
     }
 }
+
+/// The Statistics tab, mounted only while it is open. The history is read when
+/// the tab opens and again only when it changes, never while typing.
+#[component]
+fn StatisticsTab(
+    stats_view: Signal<Option<String>>,
+    session: Signal<PracticeSession>,
+    current_tab: Signal<Option<String>>,
+    show_reset_dialog: Signal<bool>,
+    migration_report: Signal<MigrationReport>,
+) -> Element {
+    let history = use_context::<Signal<History>>();
+    let records = use_memo(move || history.read().load_records());
+
+    rsx! {
+        div {
+            class: "space-y-6",
+            Tabs {
+                class: "w-full",
+                value: stats_view,
+                on_value_change: move |value| stats_view.set(Some(value)),
+
+                TabList {
+                    class: "tabs-boxed w-fit bg-white/80 shadow-sm p-1 rounded-full",
+                    TabTrigger {
+                        class: "tab px-5 py-1 text-sm font-semibold",
+                        index: 0usize,
+                        value: StatsView::Overview.to_string(),
+                        "Overview"
+                    }
+                    TabTrigger {
+                        class: "tab px-5 py-1 text-sm font-semibold",
+                        index: 1usize,
+                        value: StatsView::Characters.to_string(),
+                        "Characters"
+                    }
+                    TabTrigger {
+                        class: "tab px-5 py-1 text-sm font-semibold",
+                        index: 2usize,
+                        value: StatsView::Keyboard.to_string(),
+                        "Keyboard"
+                    }
+                    TabTrigger {
+                        class: "tab px-5 py-1 text-sm font-semibold",
+                        index: 3usize,
+                        value: StatsView::Confusions.to_string(),
+                        "Confusions"
+                    }
+                    TabTrigger {
+                        class: "tab px-5 py-1 text-sm font-semibold",
+                        index: 4usize,
+                        value: StatsView::Exercises.to_string(),
+                        "Exercises"
+                    }
+                }
+
+                TabContent {
+                    index: 0usize,
+                    value: StatsView::Overview.to_string(),
+                    if stats_view() == Some(StatsView::Overview.to_string()) {
+                        div {
+                            class: "space-y-6",
+                            UnreadableSessions { report: migration_report() }
+
+                            StatisticsDisplay {
+                                stats: history.read().statistics(),
+                                records
+                            }
+
+                            ProgressCharts { records }
+
+                            GoalProjection { records }
+
+                            PracticeHeatmap { records }
+
+                            SessionHistory { records }
+
+                            TrashBin {}
+
+                            SessionReplay { records }
+
+                            HistoryTransfer { records }
+
+                            ServerSync {}
+                        }
+                    }
+                }
+
+                TabContent {
+                    index: 1usize,
+                    value: StatsView::Characters.to_string(),
+                    if stats_view() == Some(StatsView::Characters.to_string()) {
+                        CharacterInsights { records }
+                    }
+                }
+
+                TabContent {
+                    index: 2usize,
+                    value: StatsView::Keyboard.to_string(),
+                    if stats_view() == Some(StatsView::Keyboard.to_string()) {
+                        KeyboardHeatmap { records }
+                    }
+                }
+
+                TabContent {
+                    index: 3usize,
+                    value: StatsView::Confusions.to_string(),
+                    if stats_view() == Some(StatsView::Confusions.to_string()) {
+                        ConfusionView {
+                            records,
+                            on_drill: move |text: String| {
+                                session.write().start_drill(text);
+                                current_tab.set(Some(AppTab::Practice.to_string()));
+                            }
+                        }
+                    }
+                }
+
+                TabContent {
+                    index: 4usize,
+                    value: StatsView::Exercises.to_string(),
+                    if stats_view() == Some(StatsView::Exercises.to_string()) {
+                        ExerciseLeaderboard { records }
+                    }
+                }
+            }
+
+            div {
+                class: "flex flex-wrap gap-3",
+                Button {
+                    variant: ButtonVariant::Ghost,
+                    onclick: move |_| current_tab.set(Some(AppTab::Practice.to_string())),
+                    "Back to Practice"
+                }
+                Button {
+                    variant: ButtonVariant::Primary,
+                    onclick: move |_| show_reset_dialog.set(true),
+                    "Reset All Progress"
+                }
+            }
+        }
+    }
+}
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
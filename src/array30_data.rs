@@ -28106,7 +28106,8 @@ pub const ARRAY30_ENTRIES: &[(char, &str)] = &[
 ];
 
 pub fn get_array30_code(c: char) -> Option<&'static str> {
-    ARRAY30_ENTRIES.binary_search_by_key(&c, |&(k, _)| k)
+    ARRAY30_ENTRIES
+        .binary_search_by_key(&c, |&(k, _)| k)
         .ok()
         .map(|i| ARRAY30_ENTRIES[i].1)
 }
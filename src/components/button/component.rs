@@ -37,10 +37,14 @@ pub fn Button(
     onmouseup: Option<EventHandler<MouseEvent>>,
     children: Element,
 ) -> Element {
-    let base = attributes!(button {
-        class: "btn",
-    });
-    let merged = merge_attributes(vec![base, attributes!(button { class: variant.class() }), attributes]);
+    let base = attributes!(button { class: "btn" });
+    let merged = merge_attributes(vec![
+        base,
+        attributes!(button {
+            class: variant.class()
+        }),
+        attributes,
+    ]);
 
     rsx! {
         button {
@@ -0,0 +1,164 @@
+use crate::analytics::{CharacterReport, CharacterSummary};
+use crate::components::badge::{Badge, BadgeVariant};
+use crate::components::card::{Card, CardContent, CardDescription, CardHeader, CardTitle};
+use crate::storage::SessionRecord;
+use dioxus::prelude::*;
+
+const REPORT_ROWS: usize = 10;
+
+#[component]
+pub fn CharacterInsights(records: ReadSignal<Vec<SessionRecord>>) -> Element {
+    let report = use_memo(move || CharacterReport::from_records(&records.read()));
+    let report = report.read();
+    if report.characters.is_empty() {
+        return rsx! {
+            Card {
+                class: "border border-dashed border-base-300",
+                CardHeader {
+                    CardTitle { "No character data yet" }
+                    CardDescription {
+                        "Finish a practice session to see which characters take longest and which get mistyped."
+                    }
+                }
+            }
+        };
+    }
+
+    let slowest: Vec<CharacterSummary> = report.slowest(REPORT_ROWS).into_iter().cloned().collect();
+    let missed: Vec<CharacterSummary> = report
+        .most_error_prone(REPORT_ROWS)
+        .into_iter()
+        .cloned()
+        .collect();
+    let slow_codes: Vec<(String, String, String)> = report
+        .slowest_codes(REPORT_ROWS)
+        .into_iter()
+        .map(|group| {
+            (
+                group.code.replace('|', " / "),
+                group.characters.iter().collect(),
+                format_latency(group.mean_latency_ms()),
+            )
+        })
+        .collect();
+
+    rsx! {
+        div {
+            class: "grid gap-4 xl:grid-cols-3",
+
+            Card {
+                CardHeader {
+                    CardTitle { "Slowest characters" }
+                    CardDescription { "Average time from the previous correct character." }
+                }
+                CardContent {
+                    table {
+                        class: "table table-sm",
+                        thead {
+                            tr {
+                                th { "Char" }
+                                th { "Code" }
+                                th { class: "text-right", "Avg" }
+                                th { class: "text-right", "Seen" }
+                            }
+                        }
+                        tbody {
+                            for summary in slowest {
+                                tr {
+                                    key: "{summary.ch}",
+                                    td { class: "text-xl", "{summary.ch}" }
+                                    td { CodeCell { code: summary.code } }
+                                    td { class: "text-right font-mono", {format_latency(summary.mean_latency_ms())} }
+                                    td { class: "text-right", "{summary.timed}" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            Card {
+                CardHeader {
+                    CardTitle { "Most error-prone" }
+                    CardDescription { "Mistypes recorded while the character was expected." }
+                }
+                CardContent {
+                    if missed.is_empty() {
+                        Badge { variant: BadgeVariant::Outline, "No mistakes recorded" }
+                    } else {
+                        table {
+                            class: "table table-sm",
+                            thead {
+                                tr {
+                                    th { "Char" }
+                                    th { "Code" }
+                                    th { class: "text-right", "Errors" }
+                                    th { class: "text-right", "Per try" }
+                                }
+                            }
+                            tbody {
+                                for summary in missed {
+                                    tr {
+                                        key: "{summary.ch}",
+                                        td { class: "text-xl", "{summary.ch}" }
+                                        td { CodeCell { code: summary.code } }
+                                        td { class: "text-right", "{summary.errors}" }
+                                        td { class: "text-right font-mono", {format!("{:.2}", summary.error_rate())} }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            Card {
+                CardHeader {
+                    CardTitle { "Slowest codes" }
+                    CardDescription { "Characters that share an Array30 code, grouped." }
+                }
+                CardContent {
+                    table {
+                        class: "table table-sm",
+                        thead {
+                            tr {
+                                th { "Code" }
+                                th { "Chars" }
+                                th { class: "text-right", "Avg" }
+                            }
+                        }
+                        tbody {
+                            for (code, characters, latency) in slow_codes {
+                                tr {
+                                    key: "{code}",
+                                    td { span { class: "font-mono font-semibold", "{code}" } }
+                                    td { class: "text-lg", "{characters}" }
+                                    td { class: "text-right font-mono", "{latency}" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn CodeCell(code: Option<&'static str>) -> Element {
+    rsx! {
+        if let Some(code) = code {
+            span { class: "font-mono font-semibold", {code.replace('|', " / ")} }
+        } else {
+            span { class: "text-base-content/40", "—" }
+        }
+    }
+}
+
+fn format_latency(latency_ms: Option<f64>) -> String {
+    match latency_ms {
+        Some(ms) if ms >= 1000.0 => format!("{:.1}s", ms / 1000.0),
+        Some(ms) => format!("{ms:.0}ms"),
+        None => "—".to_string(),
+    }
+}
@@ -0,0 +1,137 @@
+use crate::analytics::{array30_key_label, Confusion, ConfusionReport};
+use crate::components::badge::{Badge, BadgeVariant};
+use crate::components::button::{Button, ButtonVariant};
+use crate::components::card::{Card, CardContent, CardDescription, CardHeader, CardTitle};
+use crate::storage::SessionRecord;
+use dioxus::prelude::*;
+
+const CONFUSION_ROWS: usize = 15;
+const DRILL_CONFUSIONS: usize = 5;
+
+#[component]
+pub fn ConfusionView(
+    records: ReadSignal<Vec<SessionRecord>>,
+    on_drill: EventHandler<String>,
+) -> Element {
+    let report = use_memo(move || ConfusionReport::from_records(&records.read()));
+    let report = report.read();
+    let drill = report.drill_text(DRILL_CONFUSIONS);
+    let rows: Vec<Confusion> = report
+        .confusions
+        .iter()
+        .take(CONFUSION_ROWS)
+        .cloned()
+        .collect();
+
+    rsx! {
+        Card {
+            CardHeader {
+                CardTitle { "Confusions" }
+                CardDescription {
+                    "Characters typed in place of the expected one, with both Array30 codes and the keys that differ."
+                }
+            }
+            CardContent {
+                if rows.is_empty() {
+                    Badge { variant: BadgeVariant::Outline, "No confusions recorded yet" }
+                } else {
+                    if let Some(text) = drill {
+                        div {
+                            class: "pb-2",
+                            Button {
+                                variant: ButtonVariant::Primary,
+                                onclick: move |_| on_drill.call(text.clone()),
+                                "Drill top {DRILL_CONFUSIONS.min(rows.len())} confusions"
+                            }
+                        }
+                    }
+                    table {
+                        class: "table table-sm",
+                        thead {
+                            tr {
+                                th { "Expected" }
+                                th { "Typed" }
+                                th { class: "text-right", "Times" }
+                                th { "Difference" }
+                                th {}
+                            }
+                        }
+                        tbody {
+                            for confusion in rows {
+                                tr {
+                                    key: "{confusion.expected}{confusion.typed}",
+                                    td { CharWithCode { ch: confusion.expected, code: confusion.expected_code } }
+                                    td { CharWithCode { ch: confusion.typed, code: confusion.typed_code } }
+                                    td { class: "text-right", "{confusion.count}" }
+                                    td { class: "text-sm", {describe_difference(&confusion)} }
+                                    td {
+                                        Button {
+                                            class: "btn-sm",
+                                            variant: ButtonVariant::Ghost,
+                                            onclick: {
+                                                let pair = ConfusionReport { confusions: vec![confusion.clone()] };
+                                                move |_| {
+                                                    if let Some(text) = pair.drill_text(1) {
+                                                        on_drill.call(text);
+                                                    }
+                                                }
+                                            },
+                                            "Drill"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn CharWithCode(ch: char, code: Option<&'static str>) -> Element {
+    rsx! {
+        span {
+            class: "inline-flex items-baseline gap-2",
+            span { class: "text-xl", "{ch}" }
+            span {
+                class: "font-mono text-sm text-base-content/70",
+                {code.map(|code| code.replace('|', " / ")).unwrap_or_else(|| "—".to_string())}
+            }
+        }
+    }
+}
+
+fn describe_difference(confusion: &Confusion) -> String {
+    if confusion.expected_code.is_none() || confusion.typed_code.is_none() {
+        return "No Array30 code to compare".to_string();
+    }
+    let differing = confusion.differing_keys();
+    if differing.is_empty() {
+        return "Same code — pick the right candidate".to_string();
+    }
+
+    differing
+        .into_iter()
+        .map(|(position, expected, typed)| {
+            format!(
+                "key {}: {} → {}",
+                position + 1,
+                describe_key(expected),
+                describe_key(typed)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn describe_key(key: Option<char>) -> String {
+    match key {
+        Some(key) => match array30_key_label(key) {
+            Some(label) => format!("{key} ({label})"),
+            None => key.to_string(),
+        },
+        None => "—".to_string(),
+    }
+}
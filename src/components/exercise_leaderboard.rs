@@ -0,0 +1,79 @@
+use crate::analytics::ExerciseReport;
+use crate::components::card::{Card, CardContent, CardDescription, CardHeader, CardTitle};
+use crate::settings::Settings;
+use crate::storage::SessionRecord;
+use dioxus::prelude::*;
+
+const REPORT_ROWS: usize = 25;
+
+/// Personal best, average and attempt count for each exercise.
+#[component]
+pub fn ExerciseLeaderboard(records: ReadSignal<Vec<SessionRecord>>) -> Element {
+    let settings = use_context::<Signal<Settings>>();
+    let speed = settings().speed_metric;
+    let report = use_memo(move || {
+        ExerciseReport::from_records(&records.read(), &settings.read().outlier_rules)
+    });
+    let report = report.read();
+    if report.exercises.is_empty() {
+        return rsx! {
+            Card {
+                class: "border border-dashed border-base-300",
+                CardHeader {
+                    CardTitle { "No exercises yet" }
+                    CardDescription {
+                        "Finish a practice session to see your best and average speed on each exercise."
+                    }
+                }
+            }
+        };
+    }
+
+    let hidden = report.exercises.len().saturating_sub(REPORT_ROWS);
+
+    rsx! {
+        Card {
+            CardHeader {
+                CardTitle { "Personal bests" }
+                CardDescription {
+                    "Each exercise on its own, most practised first, since some sentences are much harder than others."
+                }
+            }
+            CardContent {
+                div {
+                    class: "overflow-x-auto",
+                    table {
+                        class: "table table-sm",
+                        thead {
+                            tr {
+                                th { "Exercise" }
+                                th { class: "text-right", "Attempts" }
+                                th { class: "text-right", "Best {speed.unit()}" }
+                                th { class: "text-right", "Avg {speed.unit()}" }
+                                th { class: "text-right", "Avg accuracy" }
+                            }
+                        }
+                        tbody {
+                            for exercise in report.exercises.iter().take(REPORT_ROWS) {
+                                tr {
+                                    key: "{exercise.exercise}",
+                                    td { class: "max-w-xs truncate", "{exercise.exercise}" }
+                                    td { class: "text-right", "{exercise.attempts}" }
+                                    td { class: "text-right font-semibold", "{speed.convert(exercise.best_wpm):.1}" }
+                                    td { class: "text-right", "{speed.convert(exercise.average_wpm):.1}" }
+                                    td { class: "text-right", "{exercise.average_accuracy:.1}%" }
+                                }
+                            }
+                        }
+                    }
+                }
+                if hidden > 0 {
+                    p {
+                        class: "pt-2 text-sm text-base-content/70",
+                        "And {hidden} more exercises practised less often."
+                    }
+                }
+            }
+        }
+    }
+}
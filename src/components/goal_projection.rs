@@ -0,0 +1,111 @@
+use crate::analytics::{Trend, MIN_TREND_SESSIONS};
+use crate::components::card::{Card, CardContent, CardDescription, CardHeader, CardTitle};
+use crate::settings::Settings;
+use crate::storage::SessionRecord;
+use chrono::{Days, Duration, Utc};
+use dioxus::prelude::*;
+
+/// Days the "recent change" figures look back over.
+const CHANGE_DAYS: i64 = 30;
+
+/// Fitted speed and accuracy trends, and when the speed trend reaches the
+/// learner's goal.
+#[component]
+pub fn GoalProjection(records: ReadSignal<Vec<SessionRecord>>) -> Element {
+    let records = records.read();
+    let settings = use_context::<Signal<Settings>>()();
+    if records.is_empty() {
+        return rsx! {};
+    }
+
+    let speed = settings.speed_metric;
+    let kept: Vec<&SessionRecord> = records
+        .iter()
+        .filter(|record| settings.outlier_rules.rejects(record).is_none())
+        .collect();
+    let samples = |value: fn(&SessionRecord) -> f64| -> Vec<_> {
+        kept.iter()
+            .map(|record| (record.recorded_at, value(record)))
+            .collect()
+    };
+    let speed_trend = Trend::fit(&samples(|record| record.wpm));
+    let accuracy_trend = Trend::fit(&samples(|record| record.accuracy));
+
+    let now = Utc::now();
+    let month_ago = now - Duration::days(CHANGE_DAYS);
+    let goal = speed.format(settings.speed_goal_wpm);
+    let projection = speed_trend.map(|trend| {
+        if trend.at(now) >= settings.speed_goal_wpm {
+            format!("On your trend you are already at {goal}.")
+        } else if let Some(when) = trend.reaches(settings.speed_goal_wpm) {
+            let days = (when - now).num_days().max(0);
+            let weeks = ((days as f64 / 7.0).ceil() as i64).max(1);
+            let unit = if weeks == 1 { "week" } else { "weeks" };
+            let date = settings.time_zone.today() + Days::new(days as u64);
+            format!(
+                "You'll reach {goal} in about {weeks} {unit}, around {}.",
+                date.format("%b %-d, %Y")
+            )
+        } else if trend.slope <= 0.0 {
+            format!("Your speed is not trending up yet, so there is no date for {goal}.")
+        } else {
+            format!("At this pace {goal} is more than ten years away.")
+        }
+    });
+    let speed_figures = speed_trend.map(|trend| {
+        let change = speed.convert(trend.at(now) - trend.at(month_ago));
+        (
+            speed.format(trend.at(now)),
+            format!("{change:+.1} {} over {CHANGE_DAYS} days", speed.unit()),
+        )
+    });
+    let accuracy_figures = accuracy_trend.map(|trend| {
+        let change = trend.at(now) - trend.at(month_ago);
+        (
+            format!("{:.1}%", trend.at(now).min(100.0)),
+            format!("{change:+.1} points over {CHANGE_DAYS} days"),
+        )
+    });
+
+    rsx! {
+        Card {
+            CardHeader {
+                CardTitle { "Trend and goal" }
+                CardDescription {
+                    "Fitted to your sessions as a curve that climbs quickly at first and then levels off. Set the goal under Settings."
+                }
+            }
+            CardContent {
+                if let Some(projection) = projection {
+                    p { class: "text-lg font-semibold pb-4", "{projection}" }
+                } else {
+                    p {
+                        class: "text-sm text-base-content/70 pb-4",
+                        "A projection toward {goal} needs {MIN_TREND_SESSIONS} sessions on different days or times."
+                    }
+                }
+                div {
+                    class: "grid gap-4 sm:grid-cols-2",
+                    if let Some((value, change)) = speed_figures {
+                        TrendFigure { label: "Speed on the trend", value, change }
+                    }
+                    if let Some((value, change)) = accuracy_figures {
+                        TrendFigure { label: "Accuracy on the trend", value, change }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn TrendFigure(label: &'static str, value: String, change: String) -> Element {
+    rsx! {
+        div {
+            class: "rounded-2xl bg-base-200/60 px-4 py-3",
+            p { class: "text-xs font-bold uppercase tracking-widest text-base-content/60", "{label}" }
+            p { class: "text-2xl font-semibold", "{value}" }
+            p { class: "text-sm text-base-content/70", "{change}" }
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use crate::components::button::{Button, ButtonVariant};
+use crate::components::card::{Card, CardContent, CardDescription, CardHeader, CardTitle};
+use crate::storage::{export_csv, export_json, parse_import, History, SessionRecord};
+use dioxus::prelude::*;
+use dioxus_primitives::toast::{use_toast, ToastOptions};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, Url};
+
+#[component]
+pub fn HistoryTransfer(records: ReadSignal<Vec<SessionRecord>>) -> Element {
+    let toast_api = use_toast();
+    let mut history = use_context::<Signal<History>>();
+    let no_records = records.read().is_empty();
+    let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let report_download = move |result: Result<(), JsValue>| {
+        if let Err(error) = result {
+            toast_api.error(
+                "Export failed".to_string(),
+                ToastOptions::new().description(format!("{error:?}")),
+            );
+        }
+    };
+
+    let export_json_file = {
+        let filename = format!("array30-history-{date}.json");
+        move |_| match export_json(&records.read()) {
+            Ok(json) => report_download(download(&filename, "application/json", &json)),
+            Err(error) => toast_api.error(
+                "Export failed".to_string(),
+                ToastOptions::new().description(error.to_string()),
+            ),
+        }
+    };
+
+    let export_csv_file = {
+        let filename = format!("array30-history-{date}.csv");
+        // The byte order mark makes spreadsheet apps read the file as UTF-8.
+        move |_| {
+            let csv = format!("\u{feff}{}", export_csv(&records.read()));
+            report_download(download(&filename, "text/csv", &csv))
+        }
+    };
+
+    let import_file = move |event: Event<FormData>| async move {
+        let Some(file) = event.files().into_iter().next() else {
+            return;
+        };
+        let batch = match file.read_string().await {
+            Ok(data) => parse_import(&data),
+            Err(error) => Err(error.to_string()),
+        };
+        let batch = match batch {
+            Ok(batch) => batch,
+            Err(reason) => {
+                toast_api.error(
+                    format!("Could not import {}", file.name()),
+                    ToastOptions::new().description(reason),
+                );
+                return;
+            }
+        };
+
+        let unreadable = batch.failed.len();
+        match history.write().import_records(batch.records) {
+            Ok(summary) => {
+                let mut description = format!(
+                    "{} sessions added, {} already present.",
+                    summary.added, summary.duplicates
+                );
+                if unreadable > 0 {
+                    description.push_str(&format!(" {unreadable} entries could not be read."));
+                }
+                toast_api.success(
+                    "History imported".to_string(),
+                    ToastOptions::new().description(description),
+                );
+            }
+            Err(error) => toast_api.error(
+                "Import failed".to_string(),
+                ToastOptions::new().description(error.to_string()),
+            ),
+        }
+    };
+
+    rsx! {
+        Card {
+            CardHeader {
+                CardTitle { "Move your history" }
+                CardDescription {
+                    "Export to carry your progress to another machine, then import it there. Sessions already present are skipped."
+                }
+            }
+            CardContent {
+                div {
+                    class: "flex flex-wrap items-center gap-3",
+                    Button {
+                        variant: ButtonVariant::Primary,
+                        disabled: no_records,
+                        onclick: export_json_file,
+                        "Export JSON"
+                    }
+                    Button {
+                        variant: ButtonVariant::Outline,
+                        disabled: no_records,
+                        onclick: export_csv_file,
+                        "Export CSV"
+                    }
+                    label {
+                        class: "btn btn-outline",
+                        "Import JSON…"
+                        input {
+                            class: "hidden",
+                            r#type: "file",
+                            accept: ".json,application/json",
+                            onchange: import_file,
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Saves `contents` through the browser's download prompt.
+fn download(filename: &str, mime: &str, contents: &str) -> Result<(), JsValue> {
+    let options = BlobPropertyBag::new();
+    options.set_type(mime);
+    let blob = Blob::new_with_str_sequence_and_options(
+        &js_sys::Array::of1(&JsValue::from_str(contents)),
+        &options,
+    )?;
+    let url = Url::create_object_url_with_blob(&blob)?;
+
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| JsValue::from_str("no document to download from"))?;
+    let anchor: HtmlAnchorElement = document.create_element("a")?.unchecked_into();
+    anchor.set_href(&url);
+    anchor.set_download(filename);
+    anchor.click();
+    Url::revoke_object_url(&url)
+}
@@ -0,0 +1,175 @@
+use crate::analytics::{array30_key_label, KeyReport, KeyStats, FINGER_NAMES, KEYBOARD_ROWS};
+use crate::components::card::{Card, CardContent, CardDescription, CardHeader, CardTitle};
+use crate::storage::SessionRecord;
+use dioxus::prelude::*;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum HeatMetric {
+    Errors,
+    PressedInstead,
+    Latency,
+}
+
+impl HeatMetric {
+    const ALL: [HeatMetric; 3] = [
+        HeatMetric::Errors,
+        HeatMetric::PressedInstead,
+        HeatMetric::Latency,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            HeatMetric::Errors => "Errors",
+            HeatMetric::PressedInstead => "Pressed instead",
+            HeatMetric::Latency => "Latency",
+        }
+    }
+
+    fn value(self, stats: &KeyStats) -> f64 {
+        match self {
+            HeatMetric::Errors => stats.blamed_errors as f64,
+            HeatMetric::PressedInstead => stats.pressed_instead as f64,
+            HeatMetric::Latency => stats.mean_latency_ms().unwrap_or_default(),
+        }
+    }
+
+    fn format(self, value: f64) -> String {
+        match self {
+            HeatMetric::Latency => format!("{value:.0}ms"),
+            _ => format!("{value:.0}"),
+        }
+    }
+}
+
+#[component]
+pub fn KeyboardHeatmap(records: ReadSignal<Vec<SessionRecord>>) -> Element {
+    let report = use_memo(move || KeyReport::from_records(&records.read()));
+    let report = report.read();
+    let mut metric = use_signal(|| HeatMetric::Errors);
+    let current = metric();
+
+    let max_key = report
+        .keys
+        .values()
+        .map(|stats| current.value(stats))
+        .fold(0.0, f64::max);
+    let intensity = move |value: f64| relative(value, max_key);
+
+    let row_names = ["Top row", "Home row", "Bottom row"];
+    let row_bars = bars(&row_names, &report.by_row(), current);
+    let finger_bars = bars(&FINGER_NAMES, &report.by_finger(), current);
+
+    rsx! {
+        Card {
+            CardHeader {
+                CardTitle { "Keyboard heatmap" }
+                CardDescription {
+                    "Errors blame the keys of the expected code; \"pressed instead\" counts the keys of the wrong character that was typed."
+                }
+            }
+            CardContent {
+                div {
+                    class: "join pb-4",
+                    for option in HeatMetric::ALL {
+                        button {
+                            key: "{option.label()}",
+                            class: if option == current { "btn btn-sm join-item btn-primary" } else { "btn btn-sm join-item" },
+                            onclick: move |_| metric.set(option),
+                            "{option.label()}"
+                        }
+                    }
+                }
+
+                div {
+                    class: "heatmap-keyboard",
+                    for (row_index, keys) in KEYBOARD_ROWS.iter().enumerate() {
+                        div {
+                            key: "{row_index}",
+                            class: "heatmap-row",
+                            style: "padding-left: {row_index as f64 * 1.25}rem",
+                            for key in keys.iter().copied() {
+                                {
+                                    let stats = report.key(key);
+                                    let value = current.value(&stats);
+                                    let alpha = 0.08 + intensity(value) * 0.82;
+                                    let text_class = if intensity(value) > 0.55 { "heatmap-key heatmap-key-hot" } else { "heatmap-key" };
+                                    let label = array30_key_label(key).unwrap_or_default();
+                                    rsx! {
+                                        div {
+                                            key: "{key}",
+                                            class: "{text_class}",
+                                            style: "background: hsl(var(--p) / {alpha:.2})",
+                                            title: "{key} ({label}): {current.format(value)}",
+                                            span { class: "heatmap-key-cap", "{key}" }
+                                            span { class: "heatmap-key-label", "{label}" }
+                                            span { class: "heatmap-key-value", {current.format(value)} }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                div {
+                    class: "grid gap-4 pt-4 md:grid-cols-2",
+                    div {
+                        h4 { class: "text-xs font-bold uppercase tracking-widest text-base-content/60 pb-2", "By row" }
+                        for (label, value, fraction) in row_bars {
+                            HeatBar { key: "{label}", label, value, fraction }
+                        }
+                    }
+                    div {
+                        h4 { class: "text-xs font-bold uppercase tracking-widest text-base-content/60 pb-2", "By finger" }
+                        for (label, value, fraction) in finger_bars {
+                            HeatBar { key: "{label}", label, value, fraction }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn HeatBar(label: String, value: String, fraction: f64) -> Element {
+    let width = fraction * 100.0;
+
+    rsx! {
+        div {
+            class: "flex items-center gap-3 text-sm py-1",
+            span { class: "w-28 shrink-0 text-base-content/70", "{label}" }
+            div {
+                class: "h-2 flex-1 rounded-full bg-base-200 overflow-hidden",
+                div { class: "h-full bg-primary", style: "width: {width:.1}%" }
+            }
+            span { class: "w-16 text-right font-mono", "{value}" }
+        }
+    }
+}
+
+/// Label, formatted value and bar length for each group, scaled to the largest group.
+fn bars(names: &[&str], groups: &[KeyStats], metric: HeatMetric) -> Vec<(String, String, f64)> {
+    let values: Vec<f64> = groups.iter().map(|stats| metric.value(stats)).collect();
+    let max_value = values.iter().copied().fold(0.0, f64::max);
+
+    names
+        .iter()
+        .zip(values)
+        .map(|(name, value)| {
+            (
+                name.to_string(),
+                metric.format(value),
+                relative(value, max_value),
+            )
+        })
+        .collect()
+}
+
+fn relative(value: f64, max_value: f64) -> f64 {
+    if max_value > 0.0 {
+        value / max_value
+    } else {
+        0.0
+    }
+}
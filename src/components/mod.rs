@@ -1,8 +1,40 @@
+mod character_report;
+mod confusion_view;
+mod exercise_leaderboard;
+mod goal_projection;
+mod history_transfer;
+mod keyboard_heatmap;
+mod practice_calendar;
 mod practice_interface;
+mod profile_switcher;
+mod progress_charts;
+mod resume_prompt;
+mod server_sync;
+mod session_history;
+mod session_replay;
+mod settings_panel;
 mod statistics;
+mod storage_notice;
+mod trash_bin;
 
+pub use character_report::CharacterInsights;
+pub use confusion_view::ConfusionView;
+pub use exercise_leaderboard::ExerciseLeaderboard;
+pub use goal_projection::GoalProjection;
+pub use history_transfer::HistoryTransfer;
+pub use keyboard_heatmap::KeyboardHeatmap;
+pub use practice_calendar::PracticeHeatmap;
 pub use practice_interface::PracticeInterface;
+pub use profile_switcher::ProfileSwitcher;
+pub use progress_charts::ProgressCharts;
+pub use resume_prompt::ResumePrompt;
+pub use server_sync::{sync_history, ServerSync};
+pub use session_history::SessionHistory;
+pub use session_replay::SessionReplay;
+pub use settings_panel::SettingsPanel;
 pub use statistics::StatisticsDisplay;
+pub use storage_notice::UnreadableSessions;
+pub use trash_bin::TrashBin;
 pub mod alert_dialog;
 pub mod badge;
 pub mod button;
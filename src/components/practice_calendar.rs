@@ -0,0 +1,161 @@
+use crate::analytics::PracticeCalendar;
+use crate::components::badge::{Badge, BadgeVariant};
+use crate::components::card::{Card, CardContent, CardDescription, CardHeader, CardTitle};
+use crate::settings::Settings;
+use crate::storage::SessionRecord;
+use crate::time_zone::week_start;
+use chrono::{Datelike, Days, NaiveDate};
+use dioxus::prelude::*;
+
+/// Weeks shown, the current one last.
+const WEEKS: u64 = 53;
+
+/// Cell opacity for each practice level, from none to twice the goal.
+const LEVEL_ALPHA: [f64; 5] = [0.08, 0.25, 0.45, 0.7, 0.95];
+
+/// A day's date, cell opacity and minutes; `None` for days still to come.
+type Cell = Option<(NaiveDate, f64, f64)>;
+
+/// Daily practice minutes over the past year, with streaks toward the daily goal.
+#[component]
+pub fn PracticeHeatmap(records: ReadSignal<Vec<SessionRecord>>) -> Element {
+    let records = records.read();
+    let settings = use_context::<Signal<Settings>>()();
+    if records.is_empty() {
+        return rsx! {};
+    }
+
+    let zone = settings.time_zone;
+    let today = zone.today();
+    let goal_ms = settings.daily_goal_ms();
+    let calendar = PracticeCalendar::from_records(&records, zone);
+    let streaks = calendar.streaks(goal_ms, today);
+    let today_totals = calendar.day(today);
+    let today_minutes = today_totals.minutes();
+    let goal_minutes = settings.daily_goal_minutes;
+
+    let first = week_start(today) - Days::new((WEEKS - 1) * 7);
+    let weeks: Vec<(Option<String>, Vec<Cell>)> = (0..WEEKS)
+        .map(|week| {
+            let monday = first + Days::new(week * 7);
+            let month = (week == 0 || monday.day() <= 7).then(|| monday.format("%b").to_string());
+            let days = (0..7)
+                .map(|weekday| {
+                    let day = monday + Days::new(weekday);
+                    (day <= today).then(|| {
+                        let totals = calendar.day(day);
+                        let alpha = LEVEL_ALPHA[level(totals.practice_ms, goal_ms)];
+                        (day, alpha, totals.minutes())
+                    })
+                })
+                .collect();
+            (month, days)
+        })
+        .collect();
+
+    rsx! {
+        Card {
+            CardHeader {
+                div {
+                    class: "flex flex-wrap items-start justify-between gap-3",
+                    div {
+                        CardTitle { "Practice calendar" }
+                        CardDescription {
+                            "Active typing per day. Days with {goal_minutes} minutes or more keep the streak going."
+                        }
+                    }
+                    if today_totals.practice_ms >= goal_ms {
+                        Badge { variant: BadgeVariant::Secondary, "Today's goal met" }
+                    } else {
+                        Badge {
+                            variant: BadgeVariant::Outline,
+                            "Today {today_minutes:.0} of {goal_minutes} min"
+                        }
+                    }
+                }
+            }
+            CardContent {
+                div {
+                    class: "grid gap-4 pb-4 sm:grid-cols-2",
+                    StreakFigure { label: "Current streak", days: streaks.current }
+                    StreakFigure { label: "Longest streak", days: streaks.longest }
+                }
+
+                div {
+                    class: "overflow-x-auto pb-2",
+                    div {
+                        class: "inline-flex gap-[3px] text-[10px] text-base-content/60",
+                        div {
+                            class: "flex flex-col gap-[3px] pr-1",
+                            span { class: "h-3" }
+                            for (index, label) in ["Mon", "", "Wed", "", "Fri", "", ""].into_iter().enumerate() {
+                                span { key: "{index}", class: "h-3 leading-3", "{label}" }
+                            }
+                        }
+                        for (index, (month, days)) in weeks.into_iter().enumerate() {
+                            div {
+                                key: "{index}",
+                                class: "flex flex-col gap-[3px]",
+                                span { class: "h-3 w-3 leading-3 overflow-visible whitespace-nowrap", {month.unwrap_or_default()} }
+                                for (weekday, cell) in days.into_iter().enumerate() {
+                                    if let Some((day, alpha, minutes)) = cell {
+                                        div {
+                                            key: "{weekday}",
+                                            class: "h-3 w-3 rounded-sm",
+                                            style: "background: hsl(var(--p) / {alpha:.2})",
+                                            title: "{day}: {minutes:.0} min",
+                                        }
+                                    } else {
+                                        div { key: "{weekday}", class: "h-3 w-3" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                div {
+                    class: "flex items-center gap-1 pt-2 text-xs text-base-content/60",
+                    "Less"
+                    for alpha in LEVEL_ALPHA {
+                        div {
+                            key: "{alpha}",
+                            class: "h-3 w-3 rounded-sm",
+                            style: "background: hsl(var(--p) / {alpha:.2})",
+                        }
+                    }
+                    "More"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn StreakFigure(label: &'static str, days: usize) -> Element {
+    let unit = if days == 1 { "day" } else { "days" };
+
+    rsx! {
+        div {
+            class: "rounded-2xl bg-base-200/60 px-4 py-3",
+            p { class: "text-xs font-bold uppercase tracking-widest text-base-content/60", "{label}" }
+            p { class: "text-2xl font-semibold", "{days} {unit}" }
+        }
+    }
+}
+
+/// 0 for no practice, then below half the goal, below the goal, below twice
+/// the goal, and beyond.
+fn level(practice_ms: u64, goal_ms: u64) -> usize {
+    if practice_ms == 0 {
+        0
+    } else if practice_ms * 2 < goal_ms {
+        1
+    } else if practice_ms < goal_ms {
+        2
+    } else if practice_ms < goal_ms * 2 {
+        3
+    } else {
+        4
+    }
+}
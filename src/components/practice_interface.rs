@@ -1,56 +1,65 @@
 use crate::components::badge::{Badge, BadgeVariant};
 use crate::components::button::{Button, ButtonVariant};
 
+use crate::analytics::exercise_key;
 use crate::array30_data;
-use crate::logic::PracticeSession;
-use crate::storage::{HistoryManager, SessionRecord};
+use crate::logic::{PracticeMode, PracticeSession, TypingStats};
+use crate::settings::{Settings, SpeedMetric};
+use crate::storage::{History, SessionRecord, StorageError};
+use crate::time_zone::device_offset_minutes;
 use dioxus::prelude::*;
 use dioxus_primitives::toast::{use_toast, ToastOptions};
 
 #[component]
 pub fn PracticeInterface(mut session: Signal<PracticeSession>) -> Element {
     let toast_api = use_toast();
-    let mut user_input = use_signal(String::new);
-    let mut start_time_ms = use_signal(|| 0u64);
     let mut show_completion = use_signal(|| false);
+    let mut last_result = use_signal(|| None::<TypingStats>);
+    let history = use_context::<Signal<History>>();
+    let settings = use_context::<Signal<Settings>>();
 
-    let handle_input = move |event: Event<FormData>| {
-        let value = event.value();
-        user_input.set(value.clone());
-
-        if !session.read().started {
-            session.write().start();
-            start_time_ms.set(chrono::Utc::now().timestamp_millis() as u64);
-        }
+    // Saves the finished run and announces it, whether it advanced on its own
+    // or through the Next button.
+    let mut finish_exercise = move || {
+        let result = session.read().stats.clone();
+        let record = session_record(&session.read());
+        let new_best = new_best_note(history, &record, &settings.peek());
+        let title = saved_title(&new_best);
+        let description = saved_description(&result, settings.peek().speed_metric, new_best);
+        spawn(async move {
+            match save_current_session(history, record).await {
+                Ok(()) => toast_api.success(title, ToastOptions::new().description(description)),
+                Err(error) => toast_api.error(
+                    "Session not saved".to_string(),
+                    ToastOptions::new().description(error.to_string()),
+                ),
+            }
+        });
+        last_result.set(Some(result));
+    };
 
-        let elapsed =
-            (chrono::Utc::now().timestamp_millis() as u64).saturating_sub(*start_time_ms.read());
-        session.write().update_input(&value, elapsed);
+    let mut next_exercise = move || {
+        let mut new_session = session.read().clone();
+        new_session.next_exercise();
+        session.set(new_session);
+        show_completion.set(false);
+    };
 
+    let mut check_completion = move || {
+        let value = session.read().user_input.clone();
         let target_text = session.read().target_text.clone();
         let target_char_count = target_text.chars().count();
         let input_char_count = value.chars().count();
         if target_char_count > 0 {
             let matches_target = input_char_count == target_char_count
-                && value
-                    .chars()
-                    .zip(target_text.chars())
-                    .all(|(a, b)| a == b);
+                && value.chars().zip(target_text.chars()).all(|(a, b)| a == b);
             if matches_target {
                 show_completion.set(true);
-                save_current_session(&session.read());
-                toast_api.success(
-                    "Session saved".to_string(),
-                    ToastOptions::new().description("Progress recorded. Loading next challenge."),
-                );
-
-                let mut new_session = session.read().clone();
-                new_session.next_exercise();
-                session.set(new_session);
-
-                user_input.set(String::new());
-                start_time_ms.set(0);
-                show_completion.set(false);
+                if !settings.peek().auto_advance {
+                    return;
+                }
+                finish_exercise();
+                next_exercise();
             } else {
                 show_completion.set(false);
             }
@@ -59,33 +68,45 @@ pub fn PracticeInterface(mut session: Signal<PracticeSession>) -> Element {
         }
     };
 
+    let handle_input = move |event: Event<FormData>| {
+        session.write().input(&event.value(), now_ms());
+        check_completion();
+    };
+
+    let handle_composition_end = move |event: CompositionEvent| {
+        session
+            .write()
+            .end_composition(&event.data().data(), now_ms());
+        check_completion();
+    };
+
+    let handle_pause = move |_| {
+        let mut session = session.write();
+        if session.is_paused() {
+            session.resume(now_ms());
+        } else {
+            session.pause(now_ms());
+        }
+    };
+
     let handle_reset = move |_| {
-        session.set(PracticeSession::new());
-        user_input.set(String::new());
-        start_time_ms.set(0);
+        let idle_threshold_ms = session.read().idle_threshold_ms;
+        let mut fresh = PracticeSession::new();
+        fresh.idle_threshold_ms = idle_threshold_ms;
+        session.set(fresh);
         show_completion.set(false);
+        last_result.set(None);
     };
 
     let handle_next = move |_| {
         if *show_completion.read() {
-            save_current_session(&session.read());
-            toast_api.success(
-                "Session saved".to_string(),
-                ToastOptions::new().description("Progress recorded. Loading next challenge."),
-            );
+            finish_exercise();
         }
-
-        let mut new_session = session.read().clone();
-        new_session.next_exercise();
-        session.set(new_session);
-
-        user_input.set(String::new());
-        start_time_ms.set(0);
-        show_completion.set(false);
+        next_exercise();
     };
 
     let (next_char, next_char_hint) = {
-        let input = user_input.read().clone();
+        let input = session.read().user_input.clone();
         let target = session.read().target_text.clone();
         let matched_prefix_count = input
             .chars()
@@ -108,7 +129,7 @@ pub fn PracticeInterface(mut session: Signal<PracticeSession>) -> Element {
 
             // Typing Exercise Area
             div {
-                class: "grid gap-6 lg:grid-cols-[minmax(0,1fr)_320px] practice-main-grid",
+                class: if settings.read().show_hints { "grid gap-6 lg:grid-cols-[minmax(0,1fr)_320px] practice-main-grid" } else { "grid gap-6 practice-main-grid" },
 
                 // Typing Area Wrapper
                 div {
@@ -120,9 +141,14 @@ pub fn PracticeInterface(mut session: Signal<PracticeSession>) -> Element {
                             div {
                                 class: "flex items-center gap-2 rounded-full bg-primary/10 px-3 py-1 text-xs font-bold uppercase tracking-widest text-primary",
                                 span { class: "inline-flex h-2 w-2 rounded-full bg-primary" }
-                                "Live Practice"
+                                if session.read().mode == PracticeMode::Drill { "Targeted Drill" } else { "Live Practice" }
                             }
-                            if session.read().started && !*show_completion.read() {
+                            if session.read().is_paused() {
+                                Badge {
+                                    variant: BadgeVariant::Outline,
+                                    "Paused — type or press Resume to continue"
+                                }
+                            } else if session.read().started && !*show_completion.read() {
                                 Badge {
                                     variant: BadgeVariant::Secondary,
                                     "Recording session..."
@@ -133,8 +159,11 @@ pub fn PracticeInterface(mut session: Signal<PracticeSession>) -> Element {
                             class: "typing-area",
                             {
                                 let target = session.read().target_text.clone();
-                                let input = user_input.read().clone();
+                                let input = session.read().user_input.clone();
+                                let preedit = session.read().preedit.clone();
                                 let input_chars: Vec<char> = input.chars().collect();
+                                let caret = input_chars.len();
+                                let target_len = target.chars().count();
 
                                 rsx! {
                                     for (i, c) in target.chars().enumerate() {
@@ -144,7 +173,22 @@ pub fn PracticeInterface(mut session: Signal<PracticeSession>) -> Element {
                                             } else {
                                                 "char-untyped"
                                             };
-                                            rsx! { span { key: "{i}", class: "{class}", "{c}" } }
+                                            rsx! {
+                                                span {
+                                                    key: "{i}",
+                                                    class: if i == caret && !preedit.is_empty() { "{class} char-caret" } else { "{class}" },
+                                                    "{c}"
+                                                    if i == caret && !preedit.is_empty() {
+                                                        span { class: "preedit-bubble", "{preedit}" }
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                    if caret >= target_len && !preedit.is_empty() {
+                                        span {
+                                            class: "char-caret",
+                                            span { class: "preedit-bubble", "{preedit}" }
                                         }
                                     }
                                 }
@@ -157,46 +201,57 @@ pub fn PracticeInterface(mut session: Signal<PracticeSession>) -> Element {
                             textarea {
                                 class: "w-full typing-input text-base",
                                 placeholder: "Focus here and start typing...",
-                                value: "{user_input}",
-                                oninput: handle_input
+                                value: "{session.read().user_input}",
+                                oninput: handle_input,
+                                oncompositionstart: move |_| session.write().begin_composition(now_ms()),
+                                oncompositionupdate: move |event: CompositionEvent| {
+                                    session.write().update_composition(&event.data().data(), now_ms());
+                                },
+                                oncompositionend: handle_composition_end,
                             }
                         }
                     }
                 }
 
                 // Hint Box
-                div {
-                    class: "bg-accent/15 border-2 border-dashed border-accent/60 rounded-3xl p-6 flex flex-col justify-between gap-6",
+                if settings.read().show_hints {
                     div {
-                        class: "space-y-2",
-                        h3 { class: "text-xs font-bold uppercase tracking-[0.25em] text-base-content/60", "Next Key Hint" }
-                        if let Some((c, code)) = next_char_hint {
-                            div {
-                                class: "code-hint-row",
-                                span { class: "code-hint-char", "{c}" }
-                                span { class: "code-hint-arrow", "→" }
-                                CodeDisplay { code: code }
-                            }
-                        } else if let Some(c) = next_char {
-                            div {
-                                class: "code-hint-row",
-                                span { class: "code-hint-char", "{c}" }
-                            }
-                        } else {
-                            // Completed or empty
-                            div {
-                                class: "code-hint-complete",
-                                "Exercise Complete"
+                        class: "bg-accent/15 border-2 border-dashed border-accent/60 rounded-3xl p-6 flex flex-col justify-between gap-6",
+                        div {
+                            class: "space-y-2",
+                            h3 { class: "text-xs font-bold uppercase tracking-[0.25em] text-base-content/60", "Next Key Hint" }
+                            if let Some((c, code)) = next_char_hint {
+                                div {
+                                    class: "code-hint-row",
+                                    span { class: "code-hint-char", "{c}" }
+                                    span { class: "code-hint-arrow", "→" }
+                                    CodeDisplay { code: code }
+                                }
+                            } else if let Some(c) = next_char {
+                                div {
+                                    class: "code-hint-row",
+                                    span { class: "code-hint-char", "{c}" }
+                                }
+                            } else {
+                                // Completed or empty
+                                div {
+                                    class: "code-hint-complete",
+                                    "Exercise Complete"
+                                }
                             }
                         }
-                    }
-                    div {
-                        class: "rounded-2xl bg-white/80 border border-accent/40 px-4 py-3 text-xs font-bold uppercase tracking-widest text-base-content/70 text-center",
-                        "Array30 Code"
+                        div {
+                            class: "rounded-2xl bg-white/80 border border-accent/40 px-4 py-3 text-xs font-bold uppercase tracking-widest text-base-content/70 text-center",
+                            "Array30 Code"
+                        }
                     }
                 }
             }
 
+            if let Some(result) = last_result() {
+                CompletionSummary { stats: result, speed_metric: settings.read().speed_metric }
+            }
+
             // Action Footer (Outside the white card)
             div {
                 class: "flex flex-wrap items-center gap-3 pt-1",
@@ -233,6 +288,13 @@ pub fn PracticeInterface(mut session: Signal<PracticeSession>) -> Element {
                         path { d: "M20.49 15a9 9 0 1 1-2.12-9.36L23 10" }
                     }
                 }
+                Button {
+                    class: "btn-lg",
+                    variant: ButtonVariant::Outline,
+                    disabled: !session.read().started,
+                    onclick: handle_pause,
+                    if session.read().is_paused() { "Resume" } else { "Pause" }
+                }
             }
 
 
@@ -240,6 +302,44 @@ pub fn PracticeInterface(mut session: Signal<PracticeSession>) -> Element {
     }
 }
 
+#[component]
+fn CompletionSummary(stats: TypingStats, speed_metric: SpeedMetric) -> Element {
+    let items = [
+        ("Net", speed_metric.format(stats.net_wpm())),
+        ("Gross", speed_metric.format(stats.gross_wpm())),
+        ("Corrected", stats.corrected_errors.to_string()),
+        ("Uncorrected", stats.uncorrected_errors().to_string()),
+        ("Deletions", stats.deletions.to_string()),
+        (
+            "Composing",
+            format!("{:.1}s", stats.composing_ms as f64 / 1000.0),
+        ),
+        (
+            "Active",
+            format!("{:.1}s", stats.elapsed_ms as f64 / 1000.0),
+        ),
+        (
+            "Wall clock",
+            format!("{:.1}s", stats.wall_ms as f64 / 1000.0),
+        ),
+    ];
+
+    rsx! {
+        div {
+            class: "flex flex-wrap items-center gap-x-6 gap-y-2 rounded-2xl bg-white/80 border border-primary/10 px-4 py-3",
+            span { class: "text-xs font-bold uppercase tracking-widest text-primary/80", "Last exercise" }
+            for (label, value) in items {
+                span {
+                    key: "{label}",
+                    class: "text-sm text-base-content/70",
+                    "{label} "
+                    strong { class: "text-base-content", "{value}" }
+                }
+            }
+        }
+    }
+}
+
 #[component]
 fn CodeDisplay(code: &'static str) -> Element {
     // Split codes by pipe if multiple
@@ -253,21 +353,85 @@ fn CodeDisplay(code: &'static str) -> Element {
     }
 }
 
-fn save_current_session(session: &crate::logic::PracticeSession) {
-    let wpm = session.stats.wpm();
-    let accuracy = session.stats.accuracy();
+/// A note for the completion toast when the finished run is faster than every
+/// earlier attempt at the same exercise. Checked before the run is saved; a run
+/// the outlier rules reject is never a best.
+fn new_best_note(
+    history: Signal<History>,
+    record: &SessionRecord,
+    settings: &Settings,
+) -> Option<String> {
+    let rules = &settings.outlier_rules;
+    if rules.rejects(record).is_some() {
+        return None;
+    }
+    history
+        .peek()
+        .exercise_best(exercise_key(record), rules)
+        .filter(|best| record.wpm > *best)
+        .map(|best| {
+            format!(
+                "New personal best on this exercise, up from {}.",
+                settings.speed_metric.format(best)
+            )
+        })
+}
 
-    let record = SessionRecord {
-        wpm,
-        accuracy,
-        timestamp: format_timestamp(),
-        elapsed_seconds: session.stats.elapsed_seconds,
+fn saved_title(new_best: &Option<String>) -> String {
+    if new_best.is_some() {
+        "New personal best!".to_string()
+    } else {
+        "Session saved".to_string()
+    }
+}
+
+fn session_record(session: &PracticeSession) -> SessionRecord {
+    SessionRecord {
+        wpm: session.stats.wpm(),
+        accuracy: session.stats.accuracy(),
+        recorded_at: chrono::Utc::now(),
+        utc_offset_minutes: device_offset_minutes(),
+        elapsed_ms: session.stats.elapsed_ms,
+        wall_ms: session.stats.wall_ms,
         exercise_text: session.target_text.clone(),
-    };
+        mode: session.mode,
+        gross_wpm: session.stats.gross_wpm(),
+        net_wpm: session.stats.net_wpm(),
+        corrected_errors: session.stats.corrected_errors,
+        uncorrected_errors: session.stats.uncorrected_errors(),
+        deletions: session.stats.deletions,
+        composing_ms: session.stats.composing_ms,
+        events: session.events.clone(),
+        char_timings: session.char_timings.clone(),
+    }
+}
+
+/// The completion toast's text: the run's net speed and errors, and any new best.
+fn saved_description(
+    result: &TypingStats,
+    speed_metric: SpeedMetric,
+    new_best: Option<String>,
+) -> String {
+    format!(
+        "{} net, {} corrected / {} uncorrected errors. {}Loading next challenge.",
+        speed_metric.format(result.net_wpm()),
+        result.corrected_errors,
+        result.uncorrected_errors(),
+        new_best.map(|note| note + " ").unwrap_or_default()
+    )
+}
 
-    HistoryManager::save_session(record);
+/// Saves the session and resolves once the backend has committed it, so that
+/// "Session saved" is never shown for a write that is then lost.
+async fn save_current_session(
+    mut history: Signal<History>,
+    record: SessionRecord,
+) -> Result<(), StorageError> {
+    history.write().save_session(record)?;
+    let committed = history.peek().committed();
+    committed.await
 }
 
-fn format_timestamp() -> String {
-    chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
 }
@@ -0,0 +1,156 @@
+use crate::components::alert_dialog::{
+    AlertDialogAction, AlertDialogActions, AlertDialogCancel, AlertDialogContent,
+    AlertDialogDescription, AlertDialogRoot, AlertDialogTitle,
+};
+use crate::components::button::{Button, ButtonVariant};
+use crate::storage::{Profiles, StorageError};
+use dioxus::prelude::*;
+use dioxus_primitives::toast::{use_toast, ToastOptions};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ProfileDialog {
+    Create,
+    Rename,
+    Delete,
+}
+
+/// Picks the learner whose history, settings and progress are in use.
+/// `on_change` fires after the active profile changed.
+#[component]
+pub fn ProfileSwitcher(on_change: EventHandler<()>) -> Element {
+    let toast_api = use_toast();
+    let mut profiles = use_context::<Signal<Profiles>>();
+    let mut dialog = use_signal(|| None::<ProfileDialog>);
+    let mut name = use_signal(String::new);
+
+    let all = profiles.read().profiles();
+    let active = profiles.read().active();
+    let can_delete = all.len() > 1;
+
+    let report = move |result: Result<(), StorageError>| {
+        if let Err(error) = result {
+            toast_api.error(
+                "Profile not saved".to_string(),
+                ToastOptions::new().description(error.to_string()),
+            );
+        }
+    };
+
+    let handle_switch = move |event: Event<FormData>| {
+        report(profiles.write().switch(&event.value()));
+        on_change.call(());
+    };
+
+    let confirm = {
+        let active = active.clone();
+        move |_| {
+            let Some(kind) = dialog() else {
+                return;
+            };
+            match kind {
+                ProfileDialog::Create => {
+                    let created = profiles.write().create(&name());
+                    report(created.and_then(|profile| profiles.write().switch(&profile.id)));
+                    on_change.call(());
+                }
+                ProfileDialog::Rename => report(profiles.write().rename(&active.id, &name())),
+                ProfileDialog::Delete => {
+                    report(profiles.write().delete(&active.id));
+                    on_change.call(());
+                }
+            }
+            dialog.set(None);
+        }
+    };
+
+    let (title, description, action) = match dialog() {
+        Some(ProfileDialog::Create) => (
+            "New learner".to_string(),
+            "Starts with an empty history and the first lesson.".to_string(),
+            "Create",
+        ),
+        Some(ProfileDialog::Rename) => (
+            format!("Rename {}", active.name),
+            "Only the name changes; history stays as it is.".to_string(),
+            "Rename",
+        ),
+        _ => (
+            format!("Delete {}?", active.name),
+            "This permanently removes the learner with all their sessions, settings and progress."
+                .to_string(),
+            "Delete",
+        ),
+    };
+
+    rsx! {
+        div {
+            class: "flex flex-wrap items-center gap-2",
+            span { class: "text-xs font-bold uppercase tracking-widest text-base-content/60", "Learner" }
+            select {
+                class: "select select-bordered select-sm",
+                onchange: handle_switch,
+                for profile in all {
+                    option {
+                        key: "{profile.id}",
+                        value: "{profile.id}",
+                        selected: profile.id == active.id,
+                        "{profile.name}"
+                    }
+                }
+            }
+            Button {
+                class: "btn-sm",
+                variant: ButtonVariant::Ghost,
+                onclick: move |_| {
+                    name.set(String::new());
+                    dialog.set(Some(ProfileDialog::Create));
+                },
+                "New"
+            }
+            Button {
+                class: "btn-sm",
+                variant: ButtonVariant::Ghost,
+                onclick: {
+                    let current = active.name.clone();
+                    move |_| {
+                        name.set(current.clone());
+                        dialog.set(Some(ProfileDialog::Rename));
+                    }
+                },
+                "Rename"
+            }
+            Button {
+                class: "btn-sm",
+                variant: ButtonVariant::Ghost,
+                disabled: !can_delete,
+                onclick: move |_| dialog.set(Some(ProfileDialog::Delete)),
+                "Delete"
+            }
+        }
+
+        AlertDialogRoot {
+            open: dialog().is_some(),
+            on_open_change: move |open: bool| {
+                if !open {
+                    dialog.set(None);
+                }
+            },
+            AlertDialogContent {
+                AlertDialogTitle { class: "text-lg font-semibold", "{title}" }
+                AlertDialogDescription { class: "text-sm text-base-content/70", "{description}" }
+                if matches!(dialog(), Some(ProfileDialog::Create | ProfileDialog::Rename)) {
+                    input {
+                        class: "input input-bordered w-full mt-4",
+                        placeholder: "Name",
+                        value: "{name}",
+                        oninput: move |event| name.set(event.value()),
+                    }
+                }
+                AlertDialogActions {
+                    AlertDialogCancel { "Cancel" }
+                    AlertDialogAction { on_click: confirm, "{action}" }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use crate::analytics::{progress_points, ChartPoint, ChartRange, MOVING_AVERAGE_SESSIONS};
+use crate::components::button::{Button, ButtonVariant};
+use crate::components::card::{Card, CardContent, CardDescription, CardHeader, CardTitle};
+use crate::settings::Settings;
+use crate::storage::SessionRecord;
+use dioxus::prelude::*;
+
+/// The charts' SVG coordinate space; the SVG itself scales to the card's width.
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 240.0;
+const LEFT: f64 = 44.0;
+const RIGHT: f64 = 12.0;
+const TOP: f64 = 12.0;
+const BOTTOM: f64 = 28.0;
+
+/// Speed and accuracy of each saved session over time, with a moving average.
+/// Sessions the outlier rules reject are left out, as in the overview.
+#[component]
+pub fn ProgressCharts(records: ReadSignal<Vec<SessionRecord>>) -> Element {
+    let records = records.read();
+    let settings = use_context::<Signal<Settings>>()();
+    let mut range = use_signal(ChartRange::default);
+    if records.is_empty() {
+        return rsx! {};
+    }
+
+    let start = range().start(chrono::Utc::now());
+    let speed = settings.speed_metric;
+    let rules = &settings.outlier_rules;
+    let speed_points = progress_points(&records, rules, start, settings.time_zone, |record| {
+        speed.convert(record.wpm)
+    });
+    let accuracy_points = progress_points(&records, rules, start, settings.time_zone, |record| {
+        record.accuracy
+    });
+
+    rsx! {
+        Card {
+            CardHeader {
+                div {
+                    class: "flex flex-wrap items-start justify-between gap-3",
+                    div {
+                        CardTitle { "Progress" }
+                        CardDescription {
+                            "Each dot is a session; the bold line averages the last {MOVING_AVERAGE_SESSIONS}."
+                        }
+                    }
+                    div {
+                        class: "flex gap-2",
+                        for choice in ChartRange::ALL {
+                            Button {
+                                key: "{choice.label()}",
+                                class: "btn-sm",
+                                variant: if choice == range() { ButtonVariant::Primary } else { ButtonVariant::Outline },
+                                onclick: move |_| range.set(choice),
+                                "{choice.label()}"
+                            }
+                        }
+                    }
+                }
+            }
+            CardContent {
+                if speed_points.is_empty() {
+                    p {
+                        class: "text-sm text-base-content/70",
+                        "No sessions in the last {range().label()}."
+                    }
+                } else {
+                    div {
+                        class: "grid gap-6 xl:grid-cols-2",
+                        LineChart {
+                            title: format!("Speed ({})", speed.unit()),
+                            points: speed_points,
+                            floor_at_zero: true,
+                            ceiling: None,
+                            suffix: "",
+                        }
+                        LineChart {
+                            title: "Accuracy".to_string(),
+                            points: accuracy_points,
+                            floor_at_zero: false,
+                            ceiling: Some(100.0),
+                            suffix: "%",
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn LineChart(
+    title: String,
+    points: Vec<ChartPoint>,
+    floor_at_zero: bool,
+    ceiling: Option<f64>,
+    suffix: &'static str,
+) -> Element {
+    let axis = Axis::fit(
+        points.iter().flat_map(|point| [point.value, point.average]),
+        floor_at_zero,
+        ceiling,
+    );
+    let first = points[0];
+    let last = points[points.len() - 1];
+    let span_ms = (last.recorded_at - first.recorded_at).num_milliseconds() as f64;
+    let x = |point: &ChartPoint| {
+        let fraction = if span_ms > 0.0 {
+            (point.recorded_at - first.recorded_at).num_milliseconds() as f64 / span_ms
+        } else {
+            0.5
+        };
+        LEFT + fraction * (WIDTH - LEFT - RIGHT)
+    };
+    let y = |value: f64| TOP + (axis.hi - value) / (axis.hi - axis.lo) * (HEIGHT - TOP - BOTTOM);
+    let line = |value: fn(&ChartPoint) -> f64| {
+        points
+            .iter()
+            .enumerate()
+            .map(|(index, point)| {
+                let command = if index == 0 { 'M' } else { 'L' };
+                format!("{command}{:.1},{:.1}", x(point), y(value(point)))
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let raw_line = line(|point| point.value);
+    let average_line = line(|point| point.average);
+    let dots: Vec<(f64, f64)> = points
+        .iter()
+        .map(|point| (x(point), y(point.value)))
+        .collect();
+    let ticks: Vec<(f64, f64, String)> = axis
+        .ticks()
+        .map(|tick| (y(tick), y(tick) + 4.0, format!("{tick:.0}{suffix}")))
+        .collect();
+    let tick_x = LEFT - 6.0;
+    let first_day = first.day.format("%b %-d").to_string();
+    let last_day = last.day.format("%b %-d").to_string();
+    let bottom = HEIGHT - BOTTOM;
+    let date_y = HEIGHT - 8.0;
+    let right = WIDTH - RIGHT;
+
+    rsx! {
+        div {
+            class: "space-y-2",
+            div {
+                class: "flex flex-wrap items-baseline justify-between gap-2",
+                h3 { class: "font-semibold", "{title}" }
+                span {
+                    class: "text-sm text-base-content/70",
+                    "Latest {last.value:.1}{suffix}, average {last.average:.1}{suffix}"
+                }
+            }
+            svg {
+                class: "w-full h-auto",
+                view_box: "0 0 {WIDTH} {HEIGHT}",
+                role: "img",
+                "aria-label": "{title} over time",
+                for (tick_y, label_y, label) in ticks {
+                    g {
+                        key: "{label}",
+                        line {
+                            x1: "{LEFT}",
+                            x2: "{right}",
+                            y1: "{tick_y:.1}",
+                            y2: "{tick_y:.1}",
+                            class: "stroke-base-300",
+                            stroke_width: "1",
+                        }
+                        text {
+                            x: "{tick_x}",
+                            y: "{label_y:.1}",
+                            text_anchor: "end",
+                            font_size: "11",
+                            class: "fill-base-content/60",
+                            "{label}"
+                        }
+                    }
+                }
+                path {
+                    d: "{raw_line}",
+                    fill: "none",
+                    class: "stroke-primary/40",
+                    stroke_width: "1.5",
+                }
+                for (index, (cx, cy)) in dots.into_iter().enumerate() {
+                    circle {
+                        key: "{index}",
+                        cx: "{cx:.1}",
+                        cy: "{cy:.1}",
+                        r: "3",
+                        class: "fill-primary/60",
+                    }
+                }
+                path {
+                    d: "{average_line}",
+                    fill: "none",
+                    class: "stroke-primary",
+                    stroke_width: "3",
+                    stroke_linecap: "round",
+                    stroke_linejoin: "round",
+                }
+                line {
+                    x1: "{LEFT}",
+                    x2: "{right}",
+                    y1: "{bottom}",
+                    y2: "{bottom}",
+                    class: "stroke-base-content/30",
+                    stroke_width: "1",
+                }
+                text {
+                    x: "{LEFT}",
+                    y: "{date_y}",
+                    font_size: "11",
+                    class: "fill-base-content/60",
+                    "{first_day}"
+                }
+                text {
+                    x: "{right}",
+                    y: "{date_y}",
+                    text_anchor: "end",
+                    font_size: "11",
+                    class: "fill-base-content/60",
+                    "{last_day}"
+                }
+            }
+        }
+    }
+}
+
+/// A value axis with round tick steps.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Axis {
+    lo: f64,
+    hi: f64,
+    step: f64,
+}
+
+impl Axis {
+    /// About four steps covering `values`, from zero if `floor_at_zero` and
+    /// never past `ceiling`.
+    fn fit(values: impl Iterator<Item = f64>, floor_at_zero: bool, ceiling: Option<f64>) -> Self {
+        let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+            (min.min(value), max.max(value))
+        });
+        let (mut lo, mut hi) = (if floor_at_zero { 0.0 } else { min }, max);
+        if hi - lo < 1.0 {
+            if lo >= 1.0 {
+                lo = hi - 1.0;
+            } else {
+                hi = lo + 1.0;
+            }
+        }
+        let step = nice_step((hi - lo) / 4.0).max(1.0);
+        let lo = (lo / step).floor() * step;
+        let hi = (hi / step).ceil() * step;
+        let hi = ceiling.map_or(hi, |ceiling| hi.min(ceiling));
+        Axis { lo, hi, step }
+    }
+
+    fn ticks(self) -> impl Iterator<Item = f64> {
+        (0..)
+            .map(move |index| self.lo + index as f64 * self.step)
+            .take_while(move |tick| *tick <= self.hi + 1e-9)
+    }
+}
+
+/// The smallest 1, 2 or 5 times a power of ten that is at least `raw`.
+fn nice_step(raw: f64) -> f64 {
+    let magnitude = 10f64.powf(raw.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= raw)
+        .unwrap_or(10.0 * magnitude)
+}
@@ -0,0 +1,51 @@
+use crate::components::button::{Button, ButtonVariant};
+use crate::components::card::{Card, CardContent, CardDescription, CardHeader, CardTitle};
+use crate::logic::SessionSnapshot;
+use chrono::{DateTime, Local};
+use dioxus::prelude::*;
+
+/// Offers to pick up the exercise that was left unfinished when the page closed.
+#[component]
+pub fn ResumePrompt(
+    snapshot: SessionSnapshot,
+    on_resume: EventHandler<()>,
+    on_discard: EventHandler<()>,
+) -> Element {
+    let typed = snapshot.user_input.chars().count();
+    let total = snapshot.target_text.chars().count();
+    let saved_at = DateTime::from_timestamp_millis(snapshot.saved_at_ms as i64)
+        .map(|time| {
+            time.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default();
+
+    rsx! {
+        Card {
+            class: "border border-primary/40",
+            CardHeader {
+                CardTitle { "Continue where you left off?" }
+                CardDescription {
+                    "{typed} of {total} characters typed, last saved {saved_at}. The time away is not counted."
+                }
+            }
+            CardContent {
+                p { class: "mb-4 truncate text-base-content/80", "{snapshot.target_text}" }
+                div {
+                    class: "flex flex-wrap gap-3",
+                    Button {
+                        variant: ButtonVariant::Primary,
+                        onclick: move |_| on_resume.call(()),
+                        "Resume"
+                    }
+                    Button {
+                        variant: ButtonVariant::Ghost,
+                        onclick: move |_| on_discard.call(()),
+                        "Discard"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use crate::components::badge::{Badge, BadgeVariant};
+use crate::components::button::{Button, ButtonVariant};
+use crate::components::card::{Card, CardContent, CardDescription, CardHeader, CardTitle};
+use crate::storage::{
+    History, HttpTransport, RemoteSync, StorageError, SyncReport, REMOTE_SYNC_KEY,
+};
+use chrono::Local;
+use dioxus::prelude::*;
+use dioxus_primitives::toast::{use_toast, ToastOptions};
+
+/// Syncs the active history with its server, or returns `None` when none is set up.
+pub async fn sync_history(
+    mut history: Signal<History>,
+) -> Option<Result<SyncReport, StorageError>> {
+    let active = history.peek().clone();
+    let remote: RemoteSync = active.load_value(REMOTE_SYNC_KEY)?;
+    if !remote.is_configured() {
+        return None;
+    }
+    let result = active
+        .sync_remote(&HttpTransport::new(&remote), chrono::Utc::now())
+        .await;
+    // Even a failed sync may have brought sessions in. Whatever profile is active
+    // by now is reloaded, in case the learner switched while waiting.
+    let current = history.peek().clone();
+    history.set(current);
+    Some(result)
+}
+
+fn describe(report: &SyncReport) -> String {
+    let mut description = format!(
+        "{} sessions added, {} deleted, {} changes sent.",
+        report.added, report.deleted, report.pushed
+    );
+    if report.unreadable > 0 {
+        description.push_str(&format!(
+            " {} sessions could not be read by this version.",
+            report.unreadable
+        ));
+    }
+    description
+}
+
+/// Shares the history with other devices through a self-hosted sync server.
+#[component]
+pub fn ServerSync() -> Element {
+    let toast_api = use_toast();
+    let mut history = use_context::<Signal<History>>();
+    let remote = use_memo(move || {
+        history
+            .read()
+            .load_value::<RemoteSync>(REMOTE_SYNC_KEY)
+            .unwrap_or_default()
+    });
+    let mut server_url = use_signal(String::new);
+    let mut space = use_signal(String::new);
+    let mut token = use_signal(String::new);
+    let mut syncing = use_signal(|| false);
+    use_effect(move || {
+        let remote = remote();
+        server_url.set(remote.server_url);
+        space.set(remote.space);
+        token.set(remote.token);
+    });
+
+    let sync_now = move |_| {
+        let configured = remote
+            .peek()
+            .configure(&server_url.peek(), &space.peek(), &token.peek());
+        let saved = configured.and_then(|updated| {
+            history
+                .peek()
+                .save_value(REMOTE_SYNC_KEY, &updated)
+                .map_err(|error| error.to_string())
+        });
+        if let Err(reason) = saved {
+            toast_api.error(
+                "Sync not set up".to_string(),
+                ToastOptions::new().description(reason),
+            );
+            return;
+        }
+        syncing.set(true);
+        spawn(async move {
+            match sync_history(history).await {
+                Some(Ok(report)) => toast_api.success(
+                    "History synced".to_string(),
+                    ToastOptions::new().description(describe(&report)),
+                ),
+                Some(Err(error)) => toast_api.error(
+                    "Sync failed".to_string(),
+                    ToastOptions::new().description(error.to_string()),
+                ),
+                None => {}
+            }
+            syncing.set(false);
+        });
+    };
+    let stop_syncing = move |_| {
+        let active = history.peek().clone();
+        active.remove_value(REMOTE_SYNC_KEY);
+        history.set(active);
+    };
+
+    let remote = remote();
+    let status = match remote.last_synced_at {
+        Some(time) => format!(
+            "Last synced {}",
+            time.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+        ),
+        None if remote.is_configured() => "Not synced yet".to_string(),
+        None => "Off".to_string(),
+    };
+
+    rsx! {
+        Card {
+            CardHeader {
+                CardTitle { "Sync with a server" }
+                CardDescription {
+                    "Devices that use the same server and space share one history. Run array30-sync-server to host one."
+                }
+            }
+            CardContent {
+                div {
+                    class: "grid gap-3 md:grid-cols-3",
+                    input {
+                        class: "input input-bordered w-full",
+                        placeholder: "http://localhost:8787",
+                        value: "{server_url}",
+                        oninput: move |event| server_url.set(event.value()),
+                    }
+                    input {
+                        class: "input input-bordered w-full",
+                        placeholder: "Space, e.g. my-history",
+                        value: "{space}",
+                        oninput: move |event| space.set(event.value()),
+                    }
+                    input {
+                        class: "input input-bordered w-full",
+                        r#type: "password",
+                        placeholder: "Token (if the server needs one)",
+                        value: "{token}",
+                        oninput: move |event| token.set(event.value()),
+                    }
+                }
+                div {
+                    class: "mt-4 flex flex-wrap items-center gap-3",
+                    Button {
+                        variant: ButtonVariant::Primary,
+                        disabled: syncing(),
+                        onclick: sync_now,
+                        if syncing() { "Syncing…" } else { "Sync now" }
+                    }
+                    if remote.is_configured() {
+                        Button {
+                            variant: ButtonVariant::Ghost,
+                            onclick: stop_syncing,
+                            "Stop syncing"
+                        }
+                    }
+                    Badge {
+                        variant: BadgeVariant::Outline,
+                        "{status}"
+                    }
+                }
+            }
+        }
+    }
+}
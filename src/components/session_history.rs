@@ -0,0 +1,245 @@
+use crate::analytics::{SessionColumn, SessionQuery};
+use crate::components::badge::{Badge, BadgeVariant};
+use crate::components::button::{Button, ButtonVariant};
+use crate::components::card::{Card, CardContent, CardDescription, CardHeader, CardTitle};
+use crate::components::statistics::format_time;
+use crate::logic::PracticeMode;
+use crate::robust_stats::Rejection;
+use crate::settings::Settings;
+use crate::storage::{History, SessionRecord, TRASH_RETENTION_DAYS};
+use chrono::NaiveDate;
+use dioxus::prelude::*;
+use dioxus_primitives::toast::{use_toast, ToastOptions};
+
+const PAGE_SIZE: usize = 20;
+
+const MODES: [PracticeMode; 2] = [PracticeMode::Lesson, PracticeMode::Drill];
+
+/// Every saved session, sortable by any column and filtered by date, exercise
+/// and mode. A session can be moved to the trash, e.g. after an accidental
+/// paste skewed its figures.
+#[component]
+pub fn SessionHistory(records: ReadSignal<Vec<SessionRecord>>) -> Element {
+    let records = records.read();
+    let toast_api = use_toast();
+    let mut history = use_context::<Signal<History>>();
+    let settings = use_context::<Signal<Settings>>()();
+    let time_zone = settings.time_zone;
+    let speed = settings.speed_metric;
+    let mut query = use_signal(SessionQuery::default);
+    let mut page = use_signal(|| 0usize);
+
+    let current = query();
+    let matching = current.apply(&records, time_zone);
+    let total = matching.len();
+    let pages = total.div_ceil(PAGE_SIZE).max(1);
+    let shown_page = page().min(pages - 1);
+    let page_number = shown_page + 1;
+    let rules = settings.outlier_rules;
+    let rows: Vec<(String, Option<Rejection>, SessionRecord)> = matching
+        .into_iter()
+        .skip(shown_page * PAGE_SIZE)
+        .take(PAGE_SIZE)
+        .map(|record| {
+            let time = time_zone.local_time(record).format("%Y-%m-%d %H:%M");
+            (time.to_string(), rules.rejects(record), record.clone())
+        })
+        .collect();
+
+    // Any change to the filters starts again from the first page.
+    let mut filter = move |change: &dyn Fn(&mut SessionQuery)| {
+        change(&mut query.write());
+        page.set(0);
+    };
+
+    let mut delete = move |record: &SessionRecord| match history
+        .write()
+        .delete_session(record, chrono::Utc::now())
+    {
+        Ok(true) => toast_api.info(
+            "Session moved to the trash".to_string(),
+            ToastOptions::new().description(format!(
+                "It can be restored for {TRASH_RETENTION_DAYS} days."
+            )),
+        ),
+        Ok(false) => {}
+        Err(error) => toast_api.error(
+            "Session not deleted".to_string(),
+            ToastOptions::new().description(error.to_string()),
+        ),
+    };
+
+    rsx! {
+        Card {
+            CardHeader {
+                CardTitle { "Session history" }
+                CardDescription {
+                    "Every saved session. Delete one that should not count, such as one finished by pasting text."
+                }
+            }
+            CardContent {
+                if records.is_empty() {
+                    Badge {
+                        variant: BadgeVariant::Outline,
+                        "No sessions yet"
+                    }
+                } else {
+                    div {
+                        class: "flex flex-wrap items-end gap-3 pb-4",
+                        label {
+                            class: "text-sm space-y-1",
+                            span { class: "block text-base-content/70", "From" }
+                            input {
+                                class: "input input-bordered input-sm",
+                                r#type: "date",
+                                value: date_value(current.from),
+                                onchange: move |event| {
+                                    let from = parse_date(&event.value());
+                                    filter(&|query| query.from = from);
+                                },
+                            }
+                        }
+                        label {
+                            class: "text-sm space-y-1",
+                            span { class: "block text-base-content/70", "To" }
+                            input {
+                                class: "input input-bordered input-sm",
+                                r#type: "date",
+                                value: date_value(current.to),
+                                onchange: move |event| {
+                                    let to = parse_date(&event.value());
+                                    filter(&|query| query.to = to);
+                                },
+                            }
+                        }
+                        label {
+                            class: "text-sm space-y-1 flex-1 min-w-40",
+                            span { class: "block text-base-content/70", "Exercise contains" }
+                            input {
+                                class: "input input-bordered input-sm w-full",
+                                value: "{current.exercise}",
+                                oninput: move |event| {
+                                    let exercise = event.value();
+                                    filter(&|query| query.exercise = exercise.clone());
+                                },
+                            }
+                        }
+                        label {
+                            class: "text-sm space-y-1",
+                            span { class: "block text-base-content/70", "Mode" }
+                            select {
+                                class: "select select-bordered select-sm",
+                                onchange: move |event| {
+                                    let value = event.value();
+                                    let mode = MODES.into_iter().find(|mode| mode.to_string() == value);
+                                    filter(&|query| query.mode = mode);
+                                },
+                                option { value: "", selected: current.mode.is_none(), "All" }
+                                for mode in MODES {
+                                    option {
+                                        key: "{mode}",
+                                        value: "{mode}",
+                                        selected: current.mode == Some(mode),
+                                        "{mode}"
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    div {
+                        class: "overflow-x-auto",
+                        table {
+                            class: "table table-sm",
+                            thead {
+                                tr {
+                                    for column in SessionColumn::ALL {
+                                        th {
+                                            key: "{column.label()}",
+                                            button {
+                                                class: "font-semibold hover:text-primary",
+                                                onclick: move |_| query.write().sort_by(column),
+                                                if column == SessionColumn::Speed { "{speed.unit()}" } else { "{column.label()}" }
+                                                if column == current.sort {
+                                                    if current.descending { " ▼" } else { " ▲" }
+                                                }
+                                            }
+                                        }
+                                    }
+                                    th {}
+                                }
+                            }
+                            tbody {
+                                for (time, rejection, record) in rows {
+                                    tr {
+                                        key: "{record.recorded_at.timestamp_millis()}-{record.exercise_text}",
+                                        td { class: "whitespace-nowrap text-sm", "{time}" }
+                                        td { class: "max-w-xs truncate", "{record.exercise_text}" }
+                                        td { "{record.mode}" }
+                                        td { "{speed.convert(record.wpm):.1}" }
+                                        td { "{record.accuracy:.1}%" }
+                                        td { class: "whitespace-nowrap", {format_time(record.elapsed_ms)} }
+                                        td {
+                                            class: "flex items-center justify-end gap-2",
+                                            if let Some(rejection) = rejection {
+                                                span {
+                                                    title: "Left out of the overview figures",
+                                                    Badge { variant: BadgeVariant::Outline, "{rejection.label()}" }
+                                                }
+                                            }
+                                            Button {
+                                                class: "btn-sm",
+                                                variant: ButtonVariant::Ghost,
+                                                onclick: move |_| delete(&record),
+                                                "Delete"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    div {
+                        class: "flex flex-wrap items-center justify-between gap-3 pt-4",
+                        span {
+                            class: "text-sm text-base-content/70",
+                            if total == 0 {
+                                "No sessions match these filters."
+                            } else {
+                                "Page {page_number} of {pages}, {total} sessions"
+                            }
+                        }
+                        div {
+                            class: "flex gap-2",
+                            Button {
+                                class: "btn-sm",
+                                variant: ButtonVariant::Outline,
+                                disabled: shown_page == 0,
+                                onclick: move |_| page.set(shown_page.saturating_sub(1)),
+                                "Previous"
+                            }
+                            Button {
+                                class: "btn-sm",
+                                variant: ButtonVariant::Outline,
+                                disabled: shown_page + 1 >= pages,
+                                onclick: move |_| page.set(page_number),
+                                "Next"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// `YYYY-MM-DD`, as date inputs expect; empty for no date.
+fn date_value(date: Option<NaiveDate>) -> String {
+    date.map(|date| date.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()
+}
@@ -0,0 +1,243 @@
+use crate::components::badge::{Badge, BadgeVariant};
+use crate::components::button::{Button, ButtonVariant};
+use crate::components::card::{Card, CardContent, CardDescription, CardHeader, CardTitle};
+use crate::logic::{replay, InputEvent};
+use crate::settings::Settings;
+use crate::storage::SessionRecord;
+use dioxus::core::Task;
+use dioxus::prelude::*;
+use gloo_timers::future::TimeoutFuture;
+
+const REPLAY_SPEEDS: [f64; 4] = [1.0, 2.0, 4.0, 8.0];
+
+#[component]
+pub fn SessionReplay(records: ReadSignal<Vec<SessionRecord>>) -> Element {
+    let mut selected = use_signal(|| 0usize);
+    let time_zone = use_context::<Signal<Settings>>().read().time_zone;
+
+    // Most recent first; sessions saved before the event log existed cannot be replayed.
+    let records = records.read();
+    let replayable: Vec<&SessionRecord> = records
+        .iter()
+        .rev()
+        .filter(|record| !record.events.is_empty())
+        .collect();
+    let labels: Vec<String> = replayable
+        .iter()
+        .map(|record| {
+            let time = time_zone.local_time(record).format("%Y-%m-%d %H:%M");
+            format!("{time} · {}", record.exercise_text)
+        })
+        .collect();
+    let current = replayable
+        .get(selected().min(replayable.len().saturating_sub(1)))
+        .map(|record| (*record).clone());
+
+    rsx! {
+        Card {
+            CardHeader {
+                CardTitle { "Session Replay" }
+                CardDescription {
+                    "Play a recorded session back over its target text to see where the typing hesitated."
+                }
+            }
+            CardContent {
+                if let Some(record) = current {
+                    select {
+                        class: "select select-bordered w-full max-w-md",
+                        onchange: move |event| {
+                            selected.set(event.value().parse().unwrap_or_default());
+                        },
+                        for (i, label) in labels.iter().enumerate() {
+                            option {
+                                key: "{i}",
+                                value: "{i}",
+                                selected: i == selected(),
+                                "{label}"
+                            }
+                        }
+                    }
+                    div {
+                        class: "pt-4",
+                        ReplayPlayer { key: "{record.recorded_at.timestamp_millis()}", record: record }
+                    }
+                } else {
+                    Badge {
+                        variant: BadgeVariant::Outline,
+                        "No recorded sessions to replay yet"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn ReplayPlayer(record: SessionRecord) -> Element {
+    let mut cursor = use_signal(|| 0usize);
+    let mut speed = use_signal(|| 1.0f64);
+    let mut playback = use_signal(|| None::<Task>);
+
+    let events = record.events.clone();
+    let total_ms = events.last().map(|event| event.at_ms).unwrap_or_default();
+    let shown = cursor().min(events.len());
+    let clock_ms = shown
+        .checked_sub(1)
+        .map(|i| events[i].at_ms)
+        .unwrap_or_default();
+    let replayed: Vec<char> = replay(&events[..shown]).chars().collect();
+    let preedit = shown
+        .checked_sub(1)
+        .map(|i| &events[i])
+        .filter(|event| event.composing)
+        .map(|event| event.inserted.clone());
+    let longest_pause = longest_pause(&events);
+
+    let mut stop = move || {
+        if let Some(task) = playback.take() {
+            task.cancel();
+        }
+    };
+
+    let play = {
+        let events = events.clone();
+        move |_| {
+            stop();
+            if cursor() >= events.len() {
+                cursor.set(0);
+            }
+            let events = events.clone();
+            let task = spawn(async move {
+                while cursor() < events.len() {
+                    let i = cursor();
+                    let previous_ms = i
+                        .checked_sub(1)
+                        .map(|p| events[p].at_ms)
+                        .unwrap_or(events[i].at_ms);
+                    let gap_ms = events[i].at_ms.saturating_sub(previous_ms) as f64 / speed();
+                    TimeoutFuture::new(gap_ms as u32).await;
+                    cursor += 1;
+                }
+                playback.set(None);
+            });
+            playback.set(Some(task));
+        }
+    };
+
+    rsx! {
+        div {
+            class: "space-y-4",
+
+            div {
+                class: "typing-area",
+                for (i, c) in record.exercise_text.chars().enumerate() {
+                    {
+                        let class = match replayed.get(i) {
+                            Some(&typed) if typed == c => "char-correct",
+                            Some(_) => "char-incorrect",
+                            None => "char-untyped",
+                        };
+                        rsx! { span { key: "{i}", class: "{class}", "{c}" } }
+                    }
+                }
+            }
+
+            if let Some(preedit) = preedit {
+                p {
+                    class: "text-sm text-base-content/70",
+                    "Composing: "
+                    span { class: "font-mono", "{preedit}" }
+                }
+            }
+
+            // Timeline: one tick per input event, placed by time. Click a tick to jump there.
+            div {
+                class: "replay-timeline",
+                for (i, event) in events.iter().enumerate() {
+                    {
+                        let left = if total_ms > 0 { event.at_ms as f64 / total_ms as f64 * 100.0 } else { 0.0 };
+                        let kind = if !event.deleted.is_empty() {
+                            "replay-tick-delete"
+                        } else if event.composing {
+                            "replay-tick-composing"
+                        } else {
+                            "replay-tick-insert"
+                        };
+                        let active = if i < shown { "replay-tick-played" } else { "" };
+                        rsx! {
+                            span {
+                                key: "{i}",
+                                class: "replay-tick {kind} {active}",
+                                style: "left: {left:.2}%",
+                                title: "{format_clock(event.at_ms)}",
+                                onclick: move |_| {
+                                    stop();
+                                    cursor.set(i + 1);
+                                },
+                            }
+                        }
+                    }
+                }
+            }
+
+            div {
+                class: "flex flex-wrap items-center gap-3",
+                if playback.read().is_some() {
+                    Button {
+                        variant: ButtonVariant::Secondary,
+                        onclick: move |_| stop(),
+                        "Pause"
+                    }
+                } else {
+                    Button {
+                        variant: ButtonVariant::Primary,
+                        onclick: play,
+                        if shown > 0 && shown < events.len() { "Resume" } else { "Play" }
+                    }
+                }
+                Button {
+                    variant: ButtonVariant::Ghost,
+                    onclick: move |_| {
+                        stop();
+                        cursor.set(0);
+                    },
+                    "Restart"
+                }
+                div {
+                    class: "join",
+                    for option in REPLAY_SPEEDS {
+                        button {
+                            key: "{option}",
+                            class: if speed() == option { "btn btn-sm join-item btn-primary" } else { "btn btn-sm join-item" },
+                            onclick: move |_| speed.set(option),
+                            "{option}×"
+                        }
+                    }
+                }
+                span {
+                    class: "text-sm font-mono text-base-content/70",
+                    "{format_clock(clock_ms)} / {format_clock(total_ms)}"
+                }
+            }
+
+            if let Some((at_ms, pause_ms)) = longest_pause {
+                p {
+                    class: "text-sm text-base-content/70",
+                    "Longest hesitation: {format_clock(pause_ms)} before the input at {format_clock(at_ms)}."
+                }
+            }
+        }
+    }
+}
+
+/// The longest gap between consecutive events, as (time of the later event, gap length).
+fn longest_pause(events: &[InputEvent]) -> Option<(u64, u64)> {
+    events
+        .windows(2)
+        .map(|pair| (pair[1].at_ms, pair[1].at_ms.saturating_sub(pair[0].at_ms)))
+        .max_by_key(|&(_, gap)| gap)
+}
+
+fn format_clock(ms: u64) -> String {
+    format!("{}:{:04.1}", ms / 60_000, (ms % 60_000) as f64 / 1000.0)
+}
@@ -0,0 +1,280 @@
+use crate::components::card::{Card, CardContent, CardDescription, CardHeader, CardTitle};
+use crate::logic::DEFAULT_IDLE_THRESHOLD_MS;
+use crate::settings::{Settings, SpeedMetric, DEFAULT_DAILY_GOAL_MINUTES};
+use crate::time_zone::{TimeZoneChoice, FIXED_OFFSETS};
+use dioxus::prelude::*;
+
+/// Idle thresholds on offer; `None` counts every gap as typing time.
+const IDLE_THRESHOLDS: [(Option<u64>, &str); 4] = [
+    (Some(5_000), "5 seconds"),
+    (Some(DEFAULT_IDLE_THRESHOLD_MS), "10 seconds"),
+    (Some(30_000), "30 seconds"),
+    (None, "Never"),
+];
+
+/// Daily goals on offer, in minutes of active practice.
+const DAILY_GOALS: [u32; 7] = [DEFAULT_DAILY_GOAL_MINUTES, 10, 15, 20, 30, 45, 60];
+
+/// Speed caps on offer for outlier rejection, in WPM; `None` keeps every speed.
+const MAX_SPEEDS: [Option<u32>; 5] = [Some(100), Some(150), Some(200), Some(300), None];
+
+/// Shortest exercise lengths on offer for outlier rejection; 0 keeps them all.
+const MIN_LENGTHS: [usize; 5] = [0, 2, 3, 5, 10];
+
+/// The Settings tab. Changes apply at once and are saved with the profile.
+#[component]
+pub fn SettingsPanel() -> Element {
+    let mut settings = use_context::<Signal<Settings>>();
+    let current = settings();
+    let goal = current.speed_metric.convert(current.speed_goal_wpm);
+    let time_zones = [TimeZoneChoice::Device, TimeZoneChoice::AsRecorded]
+        .into_iter()
+        .chain(FIXED_OFFSETS.map(TimeZoneChoice::Fixed));
+
+    rsx! {
+        div {
+            class: "grid gap-6 lg:grid-cols-2",
+
+            Card {
+                CardHeader {
+                    CardTitle { "Practice" }
+                    CardDescription { "How exercises behave while you type." }
+                }
+                CardContent {
+                    div {
+                        class: "space-y-4",
+                        SettingToggle {
+                            label: "Show Array30 code hints",
+                            description: "The code of the next character, next to the exercise.",
+                            checked: current.show_hints,
+                            on_change: move |checked| settings.write().show_hints = checked,
+                        }
+                        SettingToggle {
+                            label: "Go to the next exercise on completion",
+                            description: "Otherwise a finished exercise waits for Save & Next Lesson.",
+                            checked: current.auto_advance,
+                            on_change: move |checked| settings.write().auto_advance = checked,
+                        }
+                        SettingRow {
+                            label: "Stop counting a pause after",
+                            description: "Longer gaps between keystrokes count as idle, not typing time.",
+                            select {
+                                class: "select select-bordered select-sm",
+                                onchange: move |event| {
+                                    settings.write().idle_threshold_ms = event.value().parse().ok();
+                                },
+                                for (threshold_ms, label) in IDLE_THRESHOLDS {
+                                    option {
+                                        key: "{label}",
+                                        value: threshold_ms.map(|ms| ms.to_string()).unwrap_or_default(),
+                                        selected: current.idle_threshold_ms == threshold_ms,
+                                        "{label}"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            Card {
+                CardHeader {
+                    CardTitle { "Goals" }
+                    CardDescription { "What the streaks and the projection on the Statistics tab aim for." }
+                }
+                CardContent {
+                    div {
+                        class: "space-y-4",
+                        SettingRow {
+                            label: "Daily practice goal",
+                            description: "Days with this much active typing extend the streak.",
+                            select {
+                                class: "select select-bordered select-sm",
+                                onchange: move |event| {
+                                    if let Ok(minutes) = event.value().parse() {
+                                        settings.write().daily_goal_minutes = minutes;
+                                    }
+                                },
+                                for minutes in DAILY_GOALS {
+                                    option {
+                                        key: "{minutes}",
+                                        value: "{minutes}",
+                                        selected: minutes == current.daily_goal_minutes,
+                                        "{minutes} minutes a day"
+                                    }
+                                }
+                            }
+                        }
+                        SettingRow {
+                            label: "Speed goal",
+                            description: "The projection estimates when your trend reaches it.",
+                            label {
+                                class: "flex items-center gap-2",
+                                input {
+                                    class: "input input-bordered input-sm w-24",
+                                    r#type: "number",
+                                    min: "1",
+                                    step: "1",
+                                    value: "{goal:.0}",
+                                    onchange: move |event| {
+                                        if let Some(goal) = event.value().parse::<f64>().ok().filter(|goal| *goal > 0.0) {
+                                            let metric = settings.peek().speed_metric;
+                                            settings.write().speed_goal_wpm = metric.to_wpm(goal);
+                                        }
+                                    },
+                                }
+                                span { class: "text-sm", "{current.speed_metric.unit()}" }
+                            }
+                        }
+                    }
+                }
+            }
+
+            Card {
+                CardHeader {
+                    CardTitle { "Statistics" }
+                    CardDescription {
+                        "Sessions left out of the overview figures. They stay in the history, flagged."
+                    }
+                }
+                CardContent {
+                    div {
+                        class: "space-y-4",
+                        SettingRow {
+                            label: "Leave out sessions faster than",
+                            description: "Speeds no one types by hand, such as pasted text.",
+                            select {
+                                class: "select select-bordered select-sm",
+                                onchange: move |event| {
+                                    settings.write().outlier_rules.max_wpm = event.value().parse().ok();
+                                },
+                                for max_wpm in MAX_SPEEDS {
+                                    option {
+                                        key: "{max_wpm:?}",
+                                        value: max_wpm.map(|wpm| wpm.to_string()).unwrap_or_default(),
+                                        selected: current.outlier_rules.max_wpm == max_wpm,
+                                        match max_wpm {
+                                            Some(wpm) => current.speed_metric.format(f64::from(wpm)),
+                                            None => "Keep every speed".to_string(),
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        SettingRow {
+                            label: "Leave out exercises shorter than",
+                            description: "A few characters say little about speed.",
+                            select {
+                                class: "select select-bordered select-sm",
+                                onchange: move |event| {
+                                    if let Ok(min_characters) = event.value().parse() {
+                                        settings.write().outlier_rules.min_characters = min_characters;
+                                    }
+                                },
+                                for min_characters in MIN_LENGTHS {
+                                    option {
+                                        key: "{min_characters}",
+                                        value: "{min_characters}",
+                                        selected: current.outlier_rules.min_characters == min_characters,
+                                        if min_characters == 0 {
+                                            "Keep every length"
+                                        } else {
+                                            "{min_characters} characters"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            Card {
+                CardHeader {
+                    CardTitle { "Display" }
+                    CardDescription { "How results and dates are shown." }
+                }
+                CardContent {
+                    div {
+                        class: "space-y-4",
+                        SettingRow {
+                            label: "Speed",
+                            description: "Saved sessions are converted, so past results stay comparable.",
+                            select {
+                                class: "select select-bordered select-sm",
+                                onchange: move |event| {
+                                    if let Some(metric) = SpeedMetric::parse(&event.value()) {
+                                        settings.write().speed_metric = metric;
+                                    }
+                                },
+                                for metric in SpeedMetric::ALL {
+                                    option {
+                                        key: "{metric.value()}",
+                                        value: metric.value(),
+                                        selected: metric == current.speed_metric,
+                                        "{metric.label()}"
+                                    }
+                                }
+                            }
+                        }
+                        SettingRow {
+                            label: "Days follow",
+                            description: "The clock that dates, days and weeks in Statistics go by.",
+                            select {
+                                class: "select select-bordered select-sm",
+                                onchange: move |event| {
+                                    if let Some(choice) = TimeZoneChoice::parse(&event.value()) {
+                                        settings.write().time_zone = choice;
+                                    }
+                                },
+                                for choice in time_zones {
+                                    option {
+                                        key: "{choice.value()}",
+                                        value: "{choice.value()}",
+                                        selected: choice == current.time_zone,
+                                        "{choice.label()}"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn SettingRow(label: &'static str, description: &'static str, children: Element) -> Element {
+    rsx! {
+        div {
+            class: "flex flex-wrap items-center justify-between gap-3",
+            div {
+                p { class: "font-medium", "{label}" }
+                p { class: "text-sm text-base-content/70", "{description}" }
+            }
+            {children}
+        }
+    }
+}
+
+#[component]
+fn SettingToggle(
+    label: &'static str,
+    description: &'static str,
+    checked: bool,
+    on_change: EventHandler<bool>,
+) -> Element {
+    rsx! {
+        SettingRow {
+            label,
+            description,
+            input {
+                class: "toggle toggle-primary",
+                r#type: "checkbox",
+                checked,
+                onchange: move |event| on_change.call(event.checked()),
+            }
+        }
+    }
+}
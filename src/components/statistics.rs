@@ -1,55 +1,107 @@
+use crate::analytics::PracticeCalendar;
 use crate::components::badge::{Badge, BadgeVariant};
 use crate::components::card::{Card, CardContent, CardDescription, CardHeader, CardTitle};
 use crate::components::separator::Separator;
-use crate::storage::Statistics;
+use crate::robust_stats::{RobustStatistics, Spread};
+use crate::settings::Settings;
+use crate::storage::{SessionRecord, Statistics};
 use dioxus::prelude::*;
 
 #[component]
-pub fn StatisticsDisplay(stats: Statistics) -> Element {
+pub fn StatisticsDisplay(stats: Statistics, records: ReadSignal<Vec<SessionRecord>>) -> Element {
+    let records = records.read();
+    let settings = use_context::<Signal<Settings>>()();
+    let zone = settings.time_zone;
+    let speed = settings.speed_metric;
+    let calendar = PracticeCalendar::from_records(&records, zone);
+    let today = calendar.day(zone.today());
+    let this_week = calendar.week_of(zone.today());
+    let robust = RobustStatistics::from_records(&records, &settings.outlier_rules);
+    let speed_spread = spread_text(robust.wpm, |wpm| format!("{:.1}", speed.convert(wpm)));
+    let accuracy_spread = spread_text(robust.accuracy, |accuracy| format!("{accuracy:.1}%"));
+
     rsx! {
         div {
             class: "space-y-6",
 
-            h2 { class: "text-2xl font-semibold", "Performance Overview" }
+            div {
+                class: "flex flex-wrap items-baseline justify-between gap-3",
+                h2 { class: "text-2xl font-semibold", "Performance Overview" }
+                p {
+                    class: "text-sm text-base-content/70",
+                    "Days follow: {zone.label()}. Change it under Settings."
+                }
+            }
+
+            if robust.rejected > 0 {
+                p {
+                    class: "text-sm text-base-content/70",
+                    "{sessions_text(robust.rejected)} left out of the speed and accuracy figures as outliers. They are flagged in the session history; the rules are under Settings."
+                }
+            }
 
             if stats.total_sessions > 0 {
                 div {
                     class: "grid gap-4 md:grid-cols-2 xl:grid-cols-3",
 
+                    PremiumStatCard {
+                        label: "Today",
+                        value: format_time(today.practice_ms),
+                        subtext: sessions_text(today.sessions)
+                    }
+
+                    PremiumStatCard {
+                        label: "This Week",
+                        value: format_time(this_week.practice_ms),
+                        subtext: sessions_text(this_week.sessions)
+                    }
+
                     PremiumStatCard {
                         label: "Total Sessions",
                         value: format!("{}", stats.total_sessions),
-                        subtext: "Sessions completed"
+                        subtext: "Sessions completed".to_string()
                     }
 
                     PremiumStatCard {
                         label: "Best Speed",
-                        value: format!("{:.1} WPM", stats.best_wpm),
-                        subtext: "Your all-time peak"
+                        value: speed.format(robust.best_wpm),
+                        subtext: "Your all-time peak".to_string()
                     }
 
                     PremiumStatCard {
                         label: "Avg Speed",
-                        value: format!("{:.1} WPM", stats.average_wpm),
-                        subtext: "Overall average"
+                        value: speed.format(robust.weighted_wpm),
+                        subtext: "Weighted by typing time".to_string()
+                    }
+
+                    PremiumStatCard {
+                        label: "Median Speed",
+                        value: speed.format(robust.wpm.median),
+                        subtext: speed_spread
                     }
 
                     PremiumStatCard {
                         label: "Max Accuracy",
-                        value: format!("{:.1}%", stats.best_accuracy),
-                        subtext: "Highest precision"
+                        value: format!("{:.1}%", robust.best_accuracy),
+                        subtext: "Highest precision".to_string()
                     }
 
                     PremiumStatCard {
                         label: "Avg Accuracy",
-                        value: format!("{:.1}%", stats.average_accuracy),
-                        subtext: "Consistency score"
+                        value: format!("{:.1}%", robust.weighted_accuracy),
+                        subtext: "Weighted by typing time".to_string()
+                    }
+
+                    PremiumStatCard {
+                        label: "Median Accuracy",
+                        value: format!("{:.1}%", robust.accuracy.median),
+                        subtext: accuracy_spread
                     }
 
                     PremiumStatCard {
                         label: "Total Practice",
-                        value: format_time(stats.total_practice_time),
-                        subtext: "Time on keys"
+                        value: format_time(stats.total_practice_ms),
+                        subtext: "Time on keys".to_string()
                     }
                 }
             } else {
@@ -74,7 +126,7 @@ pub fn StatisticsDisplay(stats: Statistics) -> Element {
 }
 
 #[component]
-fn PremiumStatCard(label: &'static str, value: String, subtext: &'static str) -> Element {
+fn PremiumStatCard(label: &'static str, value: String, subtext: String) -> Element {
     rsx! {
         Card {
             CardHeader {
@@ -95,16 +147,32 @@ fn PremiumStatCard(label: &'static str, value: String, subtext: &'static str) ->
     }
 }
 
-fn format_time(seconds: u64) -> String {
+/// "Middle 80%: 20.0 to 38.0", the range between the 10th and 90th percentiles.
+fn spread_text(spread: Spread, format: impl Fn(f64) -> String) -> String {
+    format!(
+        "Middle 80%: {} to {}",
+        format(spread.p10),
+        format(spread.p90)
+    )
+}
+
+fn sessions_text(sessions: usize) -> String {
+    match sessions {
+        1 => "1 session".to_string(),
+        sessions => format!("{sessions} sessions"),
+    }
+}
+
+pub(super) fn format_time(ms: u64) -> String {
+    let seconds = ms / 1000;
     let hours = seconds / 3600;
     let minutes = (seconds % 3600) / 60;
-    let secs = seconds % 60;
 
     if hours > 0 {
         format!("{}h {}m", hours, minutes)
     } else if minutes > 0 {
-        format!("{}m {}s", minutes, secs)
+        format!("{}m {}s", minutes, seconds % 60)
     } else {
-        format!("{}s", secs)
+        format!("{:.1}s", ms as f64 / 1000.0)
     }
 }
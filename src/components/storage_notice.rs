@@ -0,0 +1,44 @@
+use crate::components::card::{Card, CardContent, CardDescription, CardHeader, CardTitle};
+use crate::storage::MigrationReport;
+use dioxus::prelude::*;
+
+/// Lists saved sessions the startup migration could not upgrade. They stay in
+/// storage untouched; this only makes sure their absence from the stats is visible.
+#[component]
+pub fn UnreadableSessions(report: MigrationReport) -> Element {
+    if report.failed.is_empty() {
+        return rsx! {};
+    }
+
+    rsx! {
+        Card {
+            class: "border border-warning/60",
+            CardHeader {
+                CardTitle { "{report.failed.len()} saved sessions could not be read" }
+                CardDescription {
+                    "They are left in storage as they are and are not counted in the statistics below."
+                }
+            }
+            CardContent {
+                table {
+                    class: "table table-sm",
+                    thead {
+                        tr {
+                            th { "Entry" }
+                            th { "Problem" }
+                        }
+                    }
+                    tbody {
+                        for failure in report.failed {
+                            tr {
+                                key: "{failure.key}",
+                                td { class: "font-mono text-sm", "{failure.key}" }
+                                td { class: "text-sm", "{failure.reason}" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use crate::components::button::{Button, ButtonVariant};
+use crate::components::card::{Card, CardContent, CardDescription, CardHeader, CardTitle};
+use crate::storage::{History, TRASH_RETENTION_DAYS};
+use chrono::Local;
+use dioxus::prelude::*;
+use dioxus_primitives::toast::{use_toast, ToastOptions};
+
+/// Deleted sessions and resets that can still be undone. Entries are purged
+/// [`TRASH_RETENTION_DAYS`] days after the deletion.
+#[component]
+pub fn TrashBin() -> Element {
+    let toast_api = use_toast();
+    let mut history = use_context::<Signal<History>>();
+    let trash = history.read().trash();
+    if trash.is_empty() {
+        return rsx! {};
+    }
+    let entries: Vec<_> = trash
+        .into_iter()
+        .rev()
+        .map(|entry| {
+            let deleted = entry
+                .deleted_at
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M");
+            let purged = entry.expires_at().with_timezone(&Local).format("%Y-%m-%d");
+            let title = match entry.records().first() {
+                Some(record) if !entry.reset => {
+                    format!("Deleted session: {}", record.exercise_text)
+                }
+                _ => format!("Reset of {} sessions", entry.session_count()),
+            };
+            (entry, title, deleted.to_string(), purged.to_string())
+        })
+        .collect();
+
+    rsx! {
+        Card {
+            CardHeader {
+                CardTitle { "Trash" }
+                CardDescription {
+                    "Deleted sessions are kept for {TRASH_RETENTION_DAYS} days before they are removed for good."
+                }
+            }
+            CardContent {
+                ul {
+                    class: "space-y-2",
+                    for (entry, title, deleted, purged) in entries {
+                        li {
+                            key: "{entry.deleted_at.timestamp_millis()}",
+                            class: "flex flex-wrap items-center justify-between gap-3",
+                            div {
+                                p { class: "font-medium", "{title}" }
+                                p {
+                                    class: "text-sm text-base-content/70",
+                                    "{deleted} · removed for good on {purged}"
+                                }
+                            }
+                            Button {
+                                class: "btn-sm",
+                                variant: ButtonVariant::Outline,
+                                onclick: move |_| {
+                                    match history.write().restore_trash(entry.deleted_at) {
+                                        Ok(summary) => toast_api.success(
+                                            "Sessions restored".to_string(),
+                                            ToastOptions::new().description(format!(
+                                                "{} sessions are back in the history.",
+                                                summary.added
+                                            )),
+                                        ),
+                                        Err(error) => toast_api.error(
+                                            "Could not restore".to_string(),
+                                            ToastOptions::new().description(error.to_string()),
+                                        ),
+                                    }
+                                },
+                                "Restore"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -3,15 +3,55 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
 pub struct TypingStats {
     pub characters_typed: usize,
+    /// Wrong characters still present in the input (uncorrected errors).
     pub errors: usize,
     pub total_typed: usize,
-    pub elapsed_seconds: u64,
+    pub elapsed_ms: u64,
+    /// Every character entered, including ones deleted later.
+    pub total_entries: usize,
+    /// Every wrong character entered, including ones fixed later.
+    pub total_errors: usize,
+    /// Wrong characters entered and later deleted.
+    pub corrected_errors: usize,
+    /// Characters removed from the input.
+    pub deletions: usize,
+    /// Time spent inside IME compositions, a share of the session time.
+    pub composing_ms: u64,
+    /// Wall-clock time from the first keystroke, including idle and paused time.
+    pub wall_ms: u64,
+    /// Idle time beyond the threshold, left out of `elapsed_ms`.
+    pub idle_ms: u64,
+    /// Time spent explicitly paused, left out of `elapsed_ms`.
+    pub paused_ms: u64,
 }
 
 impl TypingStats {
     pub fn wpm(&self) -> f64 {
-        if self.elapsed_seconds > 0 {
-            (self.characters_typed as f64 / 5.0) / (self.elapsed_seconds as f64 / 60.0)
+        if self.elapsed_ms > 0 {
+            (self.characters_typed as f64 / CHARS_PER_WORD) / (self.elapsed_ms as f64 / 60_000.0)
+        } else {
+            0.0
+        }
+    }
+
+    pub fn uncorrected_errors(&self) -> usize {
+        self.errors
+    }
+
+    /// Speed over everything entered, ignoring whether it was right.
+    pub fn gross_wpm(&self) -> f64 {
+        if self.elapsed_ms > 0 {
+            (self.total_entries as f64 / CHARS_PER_WORD) / (self.elapsed_ms as f64 / 60_000.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Gross speed penalised by the errors that were left in.
+    pub fn net_wpm(&self) -> f64 {
+        if self.elapsed_ms > 0 {
+            let minutes = self.elapsed_ms as f64 / 60_000.0;
+            (self.gross_wpm() - self.uncorrected_errors() as f64 / minutes).max(0.0)
         } else {
             0.0
         }
@@ -26,6 +66,166 @@ impl TypingStats {
     }
 }
 
+/// One change to the practice input, timed from the start of the session.
+///
+/// Field names are shortened on disk because every session stores its full timeline.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct InputEvent {
+    #[serde(rename = "t")]
+    pub at_ms: u64,
+    /// Character offset where the change starts.
+    #[serde(rename = "p", default)]
+    pub position: usize,
+    #[serde(rename = "d", default, skip_serializing_if = "String::is_empty")]
+    pub deleted: String,
+    #[serde(rename = "i", default, skip_serializing_if = "String::is_empty")]
+    pub inserted: String,
+    /// A preedit update rather than an edit: `inserted` holds the uncommitted IME text,
+    /// and the committed input is left unchanged.
+    #[serde(rename = "c", default, skip_serializing_if = "std::ops::Not::not")]
+    pub composing: bool,
+}
+
+impl InputEvent {
+    /// Describes the edit that turns `previous` into `current`, or `None` if they are equal.
+    pub fn diff(previous: &str, current: &str, at_ms: u64) -> Option<Self> {
+        let old_chars: Vec<char> = previous.chars().collect();
+        let new_chars: Vec<char> = current.chars().collect();
+
+        let prefix = old_chars
+            .iter()
+            .zip(&new_chars)
+            .take_while(|(a, b)| a == b)
+            .count();
+        let suffix = old_chars[prefix..]
+            .iter()
+            .rev()
+            .zip(new_chars[prefix..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        let deleted: String = old_chars[prefix..old_chars.len() - suffix].iter().collect();
+        let inserted: String = new_chars[prefix..new_chars.len() - suffix].iter().collect();
+        if deleted.is_empty() && inserted.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            at_ms,
+            position: prefix,
+            deleted,
+            inserted,
+            composing: false,
+        })
+    }
+
+    /// Applies this edit to `text`, the inverse of [`InputEvent::diff`].
+    pub fn apply(&self, text: &str) -> String {
+        if self.composing {
+            return text.to_string();
+        }
+        let chars: Vec<char> = text.chars().collect();
+        let start = self.position.min(chars.len());
+        let end = (start + self.deleted.chars().count()).min(chars.len());
+
+        chars[..start]
+            .iter()
+            .copied()
+            .chain(self.inserted.chars())
+            .chain(chars[end..].iter().copied())
+            .collect()
+    }
+}
+
+/// How one target character went: how long it took to commit and how often it was mistyped.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct CharTiming {
+    #[serde(rename = "c")]
+    pub ch: char,
+    /// Time from the previous correct commit to this character's first correct commit.
+    #[serde(rename = "ms", default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    #[serde(rename = "e", default, skip_serializing_if = "is_zero")]
+    pub errors: usize,
+    /// The wrong characters entered where this one was expected, in order.
+    #[serde(rename = "w", default, skip_serializing_if = "String::is_empty")]
+    pub mistyped: String,
+}
+
+fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
+
+/// Applies `event` to `wrong_entries`, one flag per input character telling whether it
+/// was wrong when entered, and returns how many wrong characters the edit deleted.
+fn track_entries(wrong_entries: &mut Vec<bool>, target: &[char], event: &InputEvent) -> usize {
+    let start = event.position.min(wrong_entries.len());
+    let end = (start + event.deleted.chars().count()).min(wrong_entries.len());
+    let inserted = event
+        .inserted
+        .chars()
+        .enumerate()
+        .map(|(offset, c)| target.get(event.position + offset) != Some(&c));
+    wrong_entries
+        .splice(start..end, inserted)
+        .filter(|wrong| *wrong)
+        .count()
+}
+
+/// Rebuilds the input text after the given events have been applied in order.
+pub fn replay(events: &[InputEvent]) -> String {
+    events
+        .iter()
+        .fold(String::new(), |text, event| event.apply(&text))
+}
+
+/// Characters making up a "word" in WPM figures, as in Western typing tests.
+pub const CHARS_PER_WORD: f64 = 5.0;
+
+/// Gaps between keystrokes longer than this count only up to this long.
+pub const DEFAULT_IDLE_THRESHOLD_MS: u64 = 10_000;
+
+/// Where a learner is in the curriculum, saved per profile.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CurriculumProgress {
+    pub exercise_index: usize,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum PracticeMode {
+    #[default]
+    Lesson,
+    /// A drill generated from the learner's own confusions.
+    Drill,
+}
+
+impl std::fmt::Display for PracticeMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PracticeMode::Lesson => write!(f, "Lesson"),
+            PracticeMode::Drill => write!(f, "Drill"),
+        }
+    }
+}
+
+/// An unfinished exercise, saved every few seconds so it can be picked up again
+/// after the page was closed.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SessionSnapshot {
+    pub target_text: String,
+    pub user_input: String,
+    pub exercise_index: usize,
+    pub mode: PracticeMode,
+    /// Wall, idle and paused time are as of `saved_at_ms`.
+    pub stats: TypingStats,
+    pub events: Vec<InputEvent>,
+    pub char_timings: Vec<CharTiming>,
+    last_commit_ms: u64,
+    /// Milliseconds since the epoch.
+    pub saved_at_ms: u64,
+}
+
 #[derive(Clone)]
 pub struct PracticeSession {
     pub target_text: String,
@@ -33,6 +233,27 @@ pub struct PracticeSession {
     pub stats: TypingStats,
     pub started: bool,
     pub exercise_index: usize,
+    pub mode: PracticeMode,
+    pub events: Vec<InputEvent>,
+    pub composing: bool,
+    /// Uncommitted IME text, shown at the caret but never scored.
+    pub preedit: String,
+    /// One entry per target character reached so far, in target order.
+    pub char_timings: Vec<CharTiming>,
+    /// One flag per character of `user_input`: whether it was wrong when entered.
+    wrong_entries: Vec<bool>,
+    last_commit_ms: u64,
+    /// Wall-clock time (ms since the epoch) the session clock counts from.
+    clock_start_ms: Option<u64>,
+    composition_start_ms: Option<u64>,
+    /// Length of `events` when the composition began: its preedit events follow.
+    composition_events: usize,
+    /// Latest textarea value seen during the composition, committed when it ends.
+    composing_value: Option<String>,
+    /// `None` turns idle detection off.
+    pub idle_threshold_ms: Option<u64>,
+    last_activity_ms: Option<u64>,
+    paused_at_ms: Option<u64>,
 }
 
 impl PracticeSession {
@@ -43,6 +264,20 @@ impl PracticeSession {
             stats: TypingStats::default(),
             started: false,
             exercise_index: 0,
+            mode: PracticeMode::Lesson,
+            events: Vec::new(),
+            composing: false,
+            preedit: String::new(),
+            char_timings: Vec::new(),
+            wrong_entries: Vec::new(),
+            last_commit_ms: 0,
+            clock_start_ms: None,
+            composition_start_ms: None,
+            composition_events: 0,
+            composing_value: None,
+            idle_threshold_ms: Some(DEFAULT_IDLE_THRESHOLD_MS),
+            last_activity_ms: None,
+            paused_at_ms: None,
         }
     }
 
@@ -50,11 +285,140 @@ impl PracticeSession {
         self.started = true;
     }
 
+    /// Handles the textarea's `input` event. While an IME composition is open the value
+    /// still contains raw preedit, so it is held back until the composition ends.
+    pub fn input(&mut self, value: &str, now_ms: u64) {
+        self.note_activity(now_ms);
+        if self.composing {
+            self.composing_value = Some(value.to_string());
+        } else {
+            self.commit(value, now_ms, now_ms);
+        }
+    }
+
+    pub fn begin_composition(&mut self, now_ms: u64) {
+        self.note_activity(now_ms);
+        self.composing = true;
+        self.composition_start_ms = Some(now_ms);
+        self.composition_events = self.events.len();
+    }
+
+    pub fn update_composition(&mut self, preedit: &str, now_ms: u64) {
+        self.note_activity(now_ms);
+        self.preedit = preedit.to_string();
+        self.events.push(InputEvent {
+            at_ms: self.active_ms(now_ms),
+            position: self.user_input.chars().count(),
+            inserted: preedit.to_string(),
+            composing: true,
+            ..InputEvent::default()
+        });
+    }
+
+    /// Commits whatever the composition produced. Browsers that fire `input` only after
+    /// `compositionend` are covered by [`PracticeSession::input`]. `committed` is the
+    /// event's data: empty when the composition was cancelled, in which case its preedit
+    /// events are dropped, as no keystroke in it reached the input.
+    pub fn end_composition(&mut self, committed: &str, now_ms: u64) {
+        self.note_activity(now_ms);
+        let began_ms = self.composition_start_ms.take().unwrap_or(now_ms);
+        self.stats.composing_ms += now_ms.saturating_sub(began_ms);
+        self.composing = false;
+        self.preedit.clear();
+        let before = self.user_input.clone();
+        if let Some(value) = self.composing_value.take() {
+            self.commit(&value, now_ms, began_ms);
+        }
+        if committed.is_empty() && self.user_input == before {
+            self.events.truncate(self.composition_events);
+        }
+    }
+
+    /// Scores committed text. Preedit alone never starts the clock; when the first
+    /// character commits, the clock is backdated to when its composition began so the
+    /// first character is not free.
+    fn commit(&mut self, value: &str, now_ms: u64, began_ms: u64) {
+        if !self.started {
+            if value == self.user_input {
+                return;
+            }
+            self.start();
+            self.clock_start_ms = Some(began_ms);
+        }
+        self.stats.wall_ms = now_ms.saturating_sub(self.clock_start_ms.unwrap_or(now_ms));
+        let elapsed_ms = self.active_ms(now_ms);
+        self.update_input(value, elapsed_ms);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_at_ms.is_some()
+    }
+
+    /// Stops the clock until [`PracticeSession::resume`] or the next keystroke.
+    pub fn pause(&mut self, now_ms: u64) {
+        if self.started && !self.is_paused() {
+            self.note_activity(now_ms);
+            self.paused_at_ms = Some(now_ms);
+        }
+    }
+
+    pub fn resume(&mut self, now_ms: u64) {
+        if let Some(paused_at_ms) = self.paused_at_ms.take() {
+            self.stats.paused_ms += now_ms.saturating_sub(paused_at_ms);
+            self.last_activity_ms = Some(now_ms);
+        }
+    }
+
+    /// Ends any pause and trims the gap since the previous keystroke down to the idle
+    /// threshold, so a long interruption does not count as typing time.
+    fn note_activity(&mut self, now_ms: u64) {
+        self.resume(now_ms);
+        if let (true, Some(last_ms), Some(threshold_ms)) =
+            (self.started, self.last_activity_ms, self.idle_threshold_ms)
+        {
+            let gap_ms = now_ms.saturating_sub(last_ms);
+            if gap_ms > threshold_ms {
+                self.stats.idle_ms += gap_ms - threshold_ms;
+            }
+        }
+        self.last_activity_ms = Some(now_ms);
+    }
+
+    /// Session time so far with idle and paused time left out. Before the first commit
+    /// it counts from the open composition, where [`PracticeSession::commit`] backdates
+    /// the clock to, so preedit is timed the same before and after the session starts.
+    fn active_ms(&self, now_ms: u64) -> u64 {
+        let start_ms = self.clock_start_ms.or(self.composition_start_ms);
+        now_ms
+            .saturating_sub(start_ms.unwrap_or(now_ms))
+            .saturating_sub(self.stats.idle_ms + self.stats.paused_ms)
+    }
+
     pub fn update_input(&mut self, input: &str, elapsed_ms: u64) {
+        let target_chars: Vec<char> = self.target_text.chars().collect();
+
+        if let Some(event) = InputEvent::diff(&self.user_input, input, elapsed_ms) {
+            self.stats.deletions += event.deleted.chars().count();
+            // By the edit rather than by position, so characters that merely shift
+            // after an insertion or deletion do not count as corrected.
+            self.stats.corrected_errors +=
+                track_entries(&mut self.wrong_entries, &target_chars, &event);
+            for (offset, c) in event.inserted.chars().enumerate() {
+                self.stats.total_entries += 1;
+                let position = event.position + offset;
+                if target_chars.get(position) != Some(&c) {
+                    self.stats.total_errors += 1;
+                    if let Some(timing) = self.char_timing_mut(position) {
+                        timing.errors += 1;
+                        timing.mistyped.push(c);
+                    }
+                }
+            }
+            self.events.push(event);
+        }
         self.user_input = input.to_string();
 
         // Count correctly typed characters
-        let target_chars: Vec<char> = self.target_text.chars().collect();
         let input_chars: Vec<char> = input.chars().collect();
 
         let mut correct = 0;
@@ -64,18 +428,156 @@ impl PracticeSession {
             }
         }
 
+        self.record_commits(&input_chars, elapsed_ms);
+
         self.stats.total_typed = input_chars.len(); // Use character count, not byte length
         self.stats.characters_typed = correct;
         self.stats.errors = self.stats.total_typed.saturating_sub(correct);
-        self.stats.elapsed_seconds = elapsed_ms / 1000;
+        self.stats.elapsed_ms = elapsed_ms;
     }
 
+    /// Times the target characters that became correct for the first time in this update.
+    /// Characters committed together (an IME phrase) share the elapsed time evenly.
+    fn record_commits(&mut self, input_chars: &[char], elapsed_ms: u64) {
+        let matched = input_chars
+            .iter()
+            .zip(self.target_text.chars())
+            .take_while(|(typed, expected)| *typed == expected)
+            .count();
+        let first_new = self
+            .char_timings
+            .iter()
+            .take_while(|timing| timing.latency_ms.is_some())
+            .count();
+        if matched <= first_new {
+            return;
+        }
+
+        let share = elapsed_ms.saturating_sub(self.last_commit_ms) / (matched - first_new) as u64;
+        for position in first_new..matched {
+            if let Some(timing) = self.char_timing_mut(position) {
+                timing.latency_ms = Some(share);
+            }
+        }
+        self.last_commit_ms = elapsed_ms;
+    }
+
+    fn char_timing_mut(&mut self, position: usize) -> Option<&mut CharTiming> {
+        let reached = self.char_timings.len();
+        if position >= reached {
+            let missing = self
+                .target_text
+                .chars()
+                .skip(reached)
+                .take(position + 1 - reached);
+            self.char_timings.extend(missing.map(|ch| CharTiming {
+                ch,
+                ..CharTiming::default()
+            }));
+        }
+        self.char_timings.get_mut(position)
+    }
+
+    /// Moves on to the next lesson. Finishing a drill returns to the lesson it interrupted.
     pub fn next_exercise(&mut self) {
-        self.exercise_index += 1;
+        match self.mode {
+            PracticeMode::Lesson => self.exercise_index += 1,
+            PracticeMode::Drill => self.mode = PracticeMode::Lesson,
+        }
         self.target_text = Self::get_exercise(self.exercise_index);
+        self.clear_progress();
+    }
+
+    /// A fresh session at a saved curriculum position, with the learner's idle threshold.
+    pub fn restore(progress: CurriculumProgress, idle_threshold_ms: Option<u64>) -> Self {
+        let mut session = Self::new();
+        session.exercise_index = progress.exercise_index;
+        session.target_text = Self::get_exercise(progress.exercise_index);
+        session.idle_threshold_ms = idle_threshold_ms;
+        session
+    }
+
+    /// The exercise in progress as of `now_ms`, or `None` before the first keystroke.
+    /// Time since the last keystroke counts as idle or paused, as the next one would count it.
+    pub fn snapshot(&self, now_ms: u64) -> Option<SessionSnapshot> {
+        if !self.started {
+            return None;
+        }
+        let mut stats = self.stats.clone();
+        if let Some(paused_at_ms) = self.paused_at_ms {
+            stats.paused_ms += now_ms.saturating_sub(paused_at_ms);
+        } else if let (Some(last_ms), Some(threshold_ms)) =
+            (self.last_activity_ms, self.idle_threshold_ms)
+        {
+            stats.idle_ms += now_ms.saturating_sub(last_ms).saturating_sub(threshold_ms);
+        }
+        stats.wall_ms = now_ms.saturating_sub(self.clock_start_ms.unwrap_or(now_ms));
+
+        Some(SessionSnapshot {
+            target_text: self.target_text.clone(),
+            user_input: self.user_input.clone(),
+            exercise_index: self.exercise_index,
+            mode: self.mode,
+            stats,
+            events: self.events.clone(),
+            char_timings: self.char_timings.clone(),
+            last_commit_ms: self.last_commit_ms,
+            saved_at_ms: now_ms,
+        })
+    }
+
+    /// Picks a saved exercise back up, paused until the next keystroke. The time the
+    /// page was closed counts as neither typing nor wall-clock time.
+    pub fn continue_from(&mut self, snapshot: SessionSnapshot, now_ms: u64) {
+        self.mode = snapshot.mode;
+        self.exercise_index = snapshot.exercise_index;
+        self.target_text = snapshot.target_text;
+        self.clear_progress();
+        self.user_input = snapshot.user_input;
+        self.events = snapshot.events;
+        let target_chars: Vec<char> = self.target_text.chars().collect();
+        for event in self.events.iter().filter(|event| !event.composing) {
+            track_entries(&mut self.wrong_entries, &target_chars, event);
+        }
+        self.char_timings = snapshot.char_timings;
+        self.last_commit_ms = snapshot.last_commit_ms;
+        self.started = true;
+        self.clock_start_ms = Some(now_ms.saturating_sub(snapshot.stats.wall_ms));
+        self.stats = snapshot.stats;
+        self.last_activity_ms = Some(now_ms);
+        self.paused_at_ms = Some(now_ms);
+    }
+
+    /// The lesson position to save; a drill in progress saves the lesson it interrupted.
+    pub fn progress(&self) -> CurriculumProgress {
+        CurriculumProgress {
+            exercise_index: self.exercise_index,
+        }
+    }
+
+    /// Replaces the current exercise with a targeted drill text.
+    pub fn start_drill(&mut self, text: String) {
+        self.mode = PracticeMode::Drill;
+        self.target_text = text;
+        self.clear_progress();
+    }
+
+    fn clear_progress(&mut self) {
         self.user_input.clear();
         self.stats = TypingStats::default();
         self.started = false;
+        self.events.clear();
+        self.composing = false;
+        self.preedit.clear();
+        self.char_timings.clear();
+        self.wrong_entries.clear();
+        self.last_commit_ms = 0;
+        self.clock_start_ms = None;
+        self.composition_start_ms = None;
+        self.composition_events = 0;
+        self.composing_value = None;
+        self.last_activity_ms = None;
+        self.paused_at_ms = None;
     }
 
     fn get_exercise(index: usize) -> String {
@@ -102,7 +604,9 @@ impl Default for PracticeSession {
 
 #[cfg(test)]
 mod tests {
-    use super::PracticeSession;
+    use super::{
+        replay, CharTiming, InputEvent, PracticeMode, PracticeSession, SessionSnapshot, TypingStats,
+    };
 
     #[test]
     fn update_input_counts_cjk_characters_not_bytes() {
@@ -114,7 +618,20 @@ mod tests {
         assert_eq!(session.stats.total_typed, 4);
         assert_eq!(session.stats.characters_typed, 3);
         assert_eq!(session.stats.errors, 1);
-        assert_eq!(session.stats.elapsed_seconds, 2);
+        assert_eq!(session.stats.elapsed_ms, 2500);
+    }
+
+    #[test]
+    fn wpm_keeps_sub_second_precision() {
+        let stats = TypingStats {
+            characters_typed: 1,
+            errors: 0,
+            total_typed: 1,
+            elapsed_ms: 900,
+            ..TypingStats::default()
+        };
+
+        assert!((stats.wpm() - (1.0 / 5.0) / (0.9 / 60.0)).abs() < 1e-9);
     }
 
     #[test]
@@ -130,6 +647,241 @@ mod tests {
         assert_eq!(session.stats.total_typed, 0);
         assert_eq!(session.stats.characters_typed, 0);
         assert_eq!(session.stats.errors, 0);
-        assert_eq!(session.stats.elapsed_seconds, 0);
+        assert_eq!(session.stats.elapsed_ms, 0);
+    }
+
+    #[test]
+    fn finishing_a_drill_returns_to_the_interrupted_lesson() {
+        let mut session = PracticeSession::new();
+        session.next_exercise();
+        let lesson = session.target_text.clone();
+
+        session.start_drill("己已己已己".to_string());
+        session.update_input("己", 300);
+        assert_eq!(session.mode, PracticeMode::Drill);
+        assert_eq!(session.target_text, "己已己已己");
+
+        session.next_exercise();
+
+        assert_eq!(session.mode, PracticeMode::Lesson);
+        assert_eq!(session.exercise_index, 1);
+        assert_eq!(session.target_text, lesson);
+        assert!(session.events.is_empty());
+    }
+
+    #[test]
+    fn input_event_diff_captures_replacement_in_the_middle() {
+        let event = InputEvent::diff("漢x測試", "漢字測試", 1200).unwrap();
+
+        assert_eq!(event.position, 1);
+        assert_eq!(event.deleted, "x");
+        assert_eq!(event.inserted, "字");
+        assert_eq!(event.apply("漢x測試"), "漢字測試");
+        assert_eq!(InputEvent::diff("漢字", "漢字", 1300), None);
+    }
+
+    #[test]
+    fn update_input_records_a_replayable_timeline() {
+        let mut session = PracticeSession::new();
+        session.update_input("漢", 0);
+        session.update_input("漢字", 900);
+        session.update_input("漢", 1500);
+
+        assert_eq!(session.events.len(), 3);
+        assert_eq!(session.events[2].deleted, "字");
+        assert_eq!(replay(&session.events), "漢");
+        assert_eq!(replay(&session.events[..2]), "漢字");
+    }
+
+    #[test]
+    fn composition_preedit_is_not_scored_and_is_timed_separately() {
+        let mut session = PracticeSession::new();
+        session.target_text = "漢字".to_string();
+
+        session.begin_composition(10_000);
+        session.update_composition("s", 10_100);
+        session.input("s", 10_100);
+        session.update_composition("sk", 10_300);
+        session.input("sk", 10_300);
+        assert!(!session.started);
+        assert_eq!(session.user_input, "");
+        assert_eq!(session.preedit, "sk");
+
+        session.input("漢", 10_600);
+        session.end_composition("漢", 10_600);
+
+        assert!(session.started);
+        assert_eq!(session.user_input, "漢");
+        assert_eq!(session.preedit, "");
+        assert_eq!(session.stats.total_entries, 1);
+        assert_eq!(session.stats.total_errors, 0);
+        assert_eq!(session.stats.elapsed_ms, 600);
+        assert_eq!(session.stats.composing_ms, 600);
+        assert!(session.events[0].composing);
+        assert_eq!(replay(&session.events), "漢");
+    }
+
+    #[test]
+    fn idle_gaps_and_pauses_are_left_out_of_active_time() {
+        let mut session = PracticeSession::new();
+        session.target_text = "漢字測試".to_string();
+        session.idle_threshold_ms = Some(5_000);
+
+        session.input("漢", 1_000);
+        session.input("漢字", 61_000);
+        session.pause(62_000);
+        assert!(session.is_paused());
+        session.input("漢字測", 122_000);
+
+        assert!(!session.is_paused());
+        assert_eq!(session.stats.idle_ms, 55_000);
+        assert_eq!(session.stats.paused_ms, 60_000);
+        assert_eq!(session.stats.wall_ms, 121_000);
+        assert_eq!(session.stats.elapsed_ms, 6_000);
+    }
+
+    #[test]
+    fn resumed_session_leaves_out_the_time_away_and_the_pause() {
+        let mut session = PracticeSession::new();
+        session.target_text = "漢字測試".to_string();
+        session.input("漢", 1_000);
+        session.input("漢字", 3_000);
+        assert_eq!(PracticeSession::new().snapshot(3_000), None);
+
+        let snapshot = session.snapshot(4_000).unwrap();
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let snapshot: SessionSnapshot = serde_json::from_str(&json).unwrap();
+
+        let mut resumed = PracticeSession::new();
+        resumed.continue_from(snapshot, 90_000_000);
+        assert!(resumed.is_paused());
+        assert_eq!(resumed.user_input, "漢字");
+        resumed.input("漢字測", 90_005_000);
+
+        assert_eq!(resumed.stats.paused_ms, 5_000);
+        assert_eq!(resumed.stats.wall_ms, 8_000);
+        assert_eq!(resumed.stats.elapsed_ms, 3_000);
+        assert_eq!(resumed.char_timings[2].latency_ms, Some(1_000));
+    }
+
+    #[test]
+    fn abandoned_composition_does_not_start_the_clock() {
+        let mut session = PracticeSession::new();
+
+        session.begin_composition(5_000);
+        session.input("x", 5_100);
+        session.input("", 5_200);
+        session.end_composition("", 5_200);
+
+        assert!(!session.started);
+        assert!(session.user_input.is_empty());
+    }
+
+    #[test]
+    fn cancelled_composition_leaves_no_preedit_in_the_timeline() {
+        let mut session = PracticeSession::new();
+        session.target_text = "漢字".to_string();
+
+        // Cancelled before the session starts: nothing is kept.
+        session.begin_composition(1_000);
+        session.update_composition("s", 1_200);
+        session.end_composition("", 1_400);
+        assert!(session.events.is_empty());
+
+        session.begin_composition(5_000);
+        session.update_composition("s", 5_100);
+        session.input("漢", 5_400);
+        session.end_composition("漢", 5_400);
+        assert_eq!(session.events[0].at_ms, 100);
+
+        // Cancelled mid-session: its preedit goes, the earlier events stay.
+        session.begin_composition(6_000);
+        session.update_composition("k", 6_200);
+        assert_eq!(session.events[2].at_ms, 1_200);
+        session.end_composition("", 6_300);
+
+        assert_eq!(session.events.len(), 2);
+        assert!(session.events[0].composing);
+        assert_eq!(replay(&session.events), "漢");
+    }
+
+    #[test]
+    fn update_input_separates_corrected_and_uncorrected_errors() {
+        let mut session = PracticeSession::new();
+        session.target_text = "漢字測試".to_string();
+
+        session.update_input("漢x", 500);
+        session.update_input("漢", 800);
+        session.update_input("漢字測y", 1500);
+
+        assert_eq!(session.stats.total_entries, 5);
+        assert_eq!(session.stats.deletions, 1);
+        assert_eq!(session.stats.total_errors, 2);
+        assert_eq!(session.stats.corrected_errors, 1);
+        assert_eq!(session.stats.uncorrected_errors(), 1);
+    }
+
+    #[test]
+    fn insertion_in_the_middle_corrects_nothing_after_it() {
+        let mut session = PracticeSession::new();
+        session.target_text = "漢字測試".to_string();
+
+        // 字 left out: 測 and 試 land one place early and are wrong when entered.
+        session.update_input("漢測試", 500);
+        assert_eq!(session.stats.total_errors, 2);
+        session.update_input("漢字測試", 1_000);
+
+        assert_eq!(session.stats.uncorrected_errors(), 0);
+        assert_eq!(session.stats.corrected_errors, 0);
+
+        // A wrong character put in and taken out again is one correction.
+        session.update_input("漢字x測試", 1_500);
+        session.update_input("漢字測試", 2_000);
+        assert_eq!(session.stats.corrected_errors, 1);
+        assert_eq!(session.stats.deletions, 1);
+    }
+
+    #[test]
+    fn net_wpm_subtracts_uncorrected_errors_per_minute() {
+        let stats = TypingStats {
+            characters_typed: 50,
+            errors: 2,
+            total_typed: 52,
+            elapsed_ms: 60_000,
+            total_entries: 60,
+            ..TypingStats::default()
+        };
+
+        assert!((stats.gross_wpm() - 12.0).abs() < 1e-9);
+        assert!((stats.net_wpm() - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn update_input_times_each_character_once() {
+        let mut session = PracticeSession::new();
+        session.target_text = "漢字測試".to_string();
+
+        session.update_input("漢", 400);
+        session.update_input("漢x", 900);
+        session.update_input("漢", 1100);
+        session.update_input("漢字測", 2000);
+        session.update_input("漢", 2200);
+        session.update_input("漢字測", 2600);
+
+        let latencies: Vec<Option<u64>> = session
+            .char_timings
+            .iter()
+            .map(|timing| timing.latency_ms)
+            .collect();
+        assert_eq!(latencies, vec![Some(400), Some(800), Some(800)]);
+        assert_eq!(
+            session.char_timings[1],
+            CharTiming {
+                ch: '字',
+                latency_ms: Some(800),
+                errors: 1,
+                mistyped: "x".to_string(),
+            }
+        );
     }
 }
@@ -1,8 +1,12 @@
+mod analytics;
 mod app;
 mod array30_data;
 mod components;
 mod logic;
+mod robust_stats;
+mod settings;
 mod storage;
+mod time_zone;
 
 use dioxus::prelude::*;
 
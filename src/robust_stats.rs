@@ -0,0 +1,191 @@
+use crate::storage::SessionRecord;
+use serde::{Deserialize, Serialize};
+
+/// Rules for leaving implausible sessions out of the overview figures, saved in
+/// [`Settings`](crate::settings::Settings). Rejected sessions stay in the
+/// history, flagged.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct OutlierRules {
+    /// Faster than this cannot have been typed by hand, e.g. pasted text;
+    /// `None` keeps every speed.
+    pub max_wpm: Option<u32>,
+    /// Exercises shorter than this say little about speed; 0 keeps them all.
+    pub min_characters: usize,
+}
+
+impl Default for OutlierRules {
+    fn default() -> Self {
+        OutlierRules {
+            max_wpm: Some(200),
+            min_characters: 3,
+        }
+    }
+}
+
+impl OutlierRules {
+    /// Why `record` is left out of the statistics, if it is.
+    pub fn rejects(&self, record: &SessionRecord) -> Option<Rejection> {
+        if self
+            .max_wpm
+            .is_some_and(|max_wpm| record.wpm > f64::from(max_wpm))
+        {
+            Some(Rejection::TooFast)
+        } else if record.exercise_text.chars().count() < self.min_characters {
+            Some(Rejection::TooShort)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rejection {
+    TooFast,
+    TooShort,
+}
+
+impl Rejection {
+    pub fn label(self) -> &'static str {
+        match self {
+            Rejection::TooFast => "Too fast",
+            Rejection::TooShort => "Too short",
+        }
+    }
+}
+
+/// The 10th percentile, median and 90th percentile of a figure.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Spread {
+    pub p10: f64,
+    pub median: f64,
+    pub p90: f64,
+}
+
+impl Spread {
+    fn of(mut values: Vec<f64>) -> Self {
+        values.sort_by(f64::total_cmp);
+        Spread {
+            p10: percentile(&values, 10.0),
+            median: percentile(&values, 50.0),
+            p90: percentile(&values, 90.0),
+        }
+    }
+}
+
+/// Overview figures that a few odd sessions cannot skew: averages weighted by
+/// active typing time, percentiles, and outliers left out.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RobustStatistics {
+    pub kept: usize,
+    pub rejected: usize,
+    pub best_wpm: f64,
+    /// WPM over all kept typing time, so a 3-second session counts for 3 seconds.
+    pub weighted_wpm: f64,
+    pub wpm: Spread,
+    pub best_accuracy: f64,
+    pub weighted_accuracy: f64,
+    pub accuracy: Spread,
+}
+
+impl RobustStatistics {
+    pub fn from_records(records: &[SessionRecord], rules: &OutlierRules) -> Self {
+        let kept: Vec<&SessionRecord> = records
+            .iter()
+            .filter(|record| rules.rejects(record).is_none())
+            .collect();
+        let rejected = records.len() - kept.len();
+        if kept.is_empty() {
+            return RobustStatistics {
+                rejected,
+                ..RobustStatistics::default()
+            };
+        }
+
+        let speeds: Vec<f64> = kept.iter().map(|record| record.wpm).collect();
+        let accuracies: Vec<f64> = kept.iter().map(|record| record.accuracy).collect();
+        RobustStatistics {
+            kept: kept.len(),
+            rejected,
+            best_wpm: speeds.iter().copied().fold(0.0, f64::max),
+            weighted_wpm: weighted_mean(&kept, |record| record.wpm),
+            best_accuracy: accuracies.iter().copied().fold(0.0, f64::max),
+            weighted_accuracy: weighted_mean(&kept, |record| record.accuracy),
+            wpm: Spread::of(speeds),
+            accuracy: Spread::of(accuracies),
+        }
+    }
+}
+
+/// Mean of `value` weighted by active time; the plain mean when no session
+/// has any active time recorded.
+fn weighted_mean(records: &[&SessionRecord], value: impl Fn(&SessionRecord) -> f64) -> f64 {
+    let total_ms: u64 = records.iter().map(|record| record.elapsed_ms).sum();
+    if total_ms == 0 {
+        return records.iter().map(|record| value(record)).sum::<f64>() / records.len() as f64;
+    }
+    records
+        .iter()
+        .map(|record| value(record) * record.elapsed_ms as f64)
+        .sum::<f64>()
+        / total_ms as f64
+}
+
+/// The `p`th percentile of sorted `values`, interpolating between neighbours.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    match sorted.len() {
+        0 => 0.0,
+        len => {
+            let rank = p / 100.0 * (len - 1) as f64;
+            let below = rank.floor() as usize;
+            let above = rank.ceil() as usize;
+            sorted[below] + (sorted[above] - sorted[below]) * (rank - below as f64)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OutlierRules, Rejection, RobustStatistics};
+    use crate::storage::SessionRecord;
+
+    fn session(text: &str, wpm: f64, elapsed_ms: u64) -> SessionRecord {
+        SessionRecord {
+            exercise_text: text.to_string(),
+            wpm,
+            accuracy: 100.0,
+            elapsed_ms,
+            ..SessionRecord::default()
+        }
+    }
+
+    #[test]
+    fn outliers_are_left_out_and_time_weights_the_average() {
+        let records = [
+            session("天地玄黃", 20.0, 600_000),
+            session("宇宙洪荒", 40.0, 3_000),
+            session("日月盈昃", 30.0, 300_000),
+            session("辰宿列張", 900.0, 1_000),
+            session("天", 10.0, 1_000),
+        ];
+        let rules = OutlierRules::default();
+        assert_eq!(rules.rejects(&records[3]), Some(Rejection::TooFast));
+        assert_eq!(rules.rejects(&records[4]), Some(Rejection::TooShort));
+
+        let stats = RobustStatistics::from_records(&records, &rules);
+        assert_eq!((stats.kept, stats.rejected), (3, 2));
+        assert_eq!(stats.best_wpm, 40.0);
+        assert!((stats.weighted_wpm - 23.4).abs() < 0.1);
+        assert_eq!(stats.wpm.median, 30.0);
+        assert!((stats.wpm.p10 - 22.0).abs() < 1e-9);
+        assert!((stats.wpm.p90 - 38.0).abs() < 1e-9);
+
+        let keep_all = OutlierRules {
+            max_wpm: None,
+            min_characters: 0,
+        };
+        let stats = RobustStatistics::from_records(&records, &keep_all);
+        assert_eq!((stats.kept, stats.rejected), (5, 0));
+        assert_eq!(stats.best_wpm, 900.0);
+    }
+}
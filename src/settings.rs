@@ -0,0 +1,182 @@
+use crate::logic::{CHARS_PER_WORD, DEFAULT_IDLE_THRESHOLD_MS};
+use crate::robust_stats::OutlierRules;
+use crate::storage::{HistoryManager, StorageBackend, StorageError, SETTINGS_KEY, TIME_ZONE_KEY};
+use crate::time_zone::TimeZoneChoice;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const DEFAULT_DAILY_GOAL_MINUTES: u32 = 5;
+
+/// 100 characters per minute.
+pub const DEFAULT_SPEED_GOAL_WPM: f64 = 20.0;
+
+/// Everything the learner can choose, saved per profile and shared with
+/// components through context as a `Signal<Settings>`.
+///
+/// Fields missing from a saved value take their defaults, so options can be
+/// added without a migration.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    /// Gaps between keystrokes longer than this are left out of active time;
+    /// `None` counts every gap.
+    pub idle_threshold_ms: Option<u64>,
+    /// Whether the Array30 code of the next character is shown while typing.
+    pub show_hints: bool,
+    /// Whether a finished exercise is saved and the next one loaded right away,
+    /// rather than waiting for "Save & Next Lesson".
+    pub auto_advance: bool,
+    /// Active practice a day needs to keep a streak going.
+    pub daily_goal_minutes: u32,
+    /// The speed the projection on the Statistics tab works toward, in WPM.
+    pub speed_goal_wpm: f64,
+    pub speed_metric: SpeedMetric,
+    pub time_zone: TimeZoneChoice,
+    /// Sessions left out of the overview figures.
+    pub outlier_rules: OutlierRules,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            idle_threshold_ms: Some(DEFAULT_IDLE_THRESHOLD_MS),
+            show_hints: true,
+            auto_advance: true,
+            daily_goal_minutes: DEFAULT_DAILY_GOAL_MINUTES,
+            speed_goal_wpm: DEFAULT_SPEED_GOAL_WPM,
+            speed_metric: SpeedMetric::default(),
+            time_zone: TimeZoneChoice::default(),
+            outlier_rules: OutlierRules::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn daily_goal_ms(&self) -> u64 {
+        u64::from(self.daily_goal_minutes) * 60_000
+    }
+
+    /// The profile's settings. The time zone used to be saved on its own; a
+    /// profile that has not saved settings since picks it up from there.
+    pub fn load<B: StorageBackend>(history: &HistoryManager<B>) -> Self {
+        let saved: Option<Value> = history.load_value(SETTINGS_KEY);
+        let has_time_zone = saved
+            .as_ref()
+            .is_some_and(|saved| saved.get("time_zone").is_some());
+        let mut settings: Settings = saved
+            .and_then(|saved| serde_json::from_value(saved).ok())
+            .unwrap_or_default();
+        if !has_time_zone {
+            if let Some(time_zone) = history.load_value(TIME_ZONE_KEY) {
+                settings.time_zone = time_zone;
+            }
+        }
+        settings
+    }
+
+    pub fn save<B: StorageBackend>(&self, history: &HistoryManager<B>) -> Result<(), StorageError> {
+        history.save_value(SETTINGS_KEY, self)?;
+        history.remove_value(TIME_ZONE_KEY);
+        Ok(())
+    }
+}
+
+/// How typing speed is shown. Records always store WPM.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SpeedMetric {
+    /// Words per minute, a word being five characters as in Western typing tests.
+    #[default]
+    Wpm,
+    /// Characters per minute, the usual measure for Chinese input.
+    Cpm,
+}
+
+impl SpeedMetric {
+    pub const ALL: [SpeedMetric; 2] = [SpeedMetric::Wpm, SpeedMetric::Cpm];
+
+    pub fn unit(self) -> &'static str {
+        match self {
+            SpeedMetric::Wpm => "WPM",
+            SpeedMetric::Cpm => "CPM",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SpeedMetric::Wpm => "Words per minute (5 characters a word)",
+            SpeedMetric::Cpm => "Characters per minute",
+        }
+    }
+
+    /// A stored WPM figure in this metric.
+    pub fn convert(self, wpm: f64) -> f64 {
+        match self {
+            SpeedMetric::Wpm => wpm,
+            SpeedMetric::Cpm => wpm * CHARS_PER_WORD,
+        }
+    }
+
+    /// A figure in this metric as WPM, for storing.
+    pub fn to_wpm(self, value: f64) -> f64 {
+        match self {
+            SpeedMetric::Wpm => value,
+            SpeedMetric::Cpm => value / CHARS_PER_WORD,
+        }
+    }
+
+    /// A stored WPM figure in this metric, with one decimal and the unit.
+    pub fn format(self, wpm: f64) -> String {
+        format!("{:.1} {}", self.convert(wpm), self.unit())
+    }
+
+    /// A stable value for `<option>` elements, read back by [`SpeedMetric::parse`].
+    pub fn value(self) -> &'static str {
+        match self {
+            SpeedMetric::Wpm => "wpm",
+            SpeedMetric::Cpm => "cpm",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        SpeedMetric::ALL
+            .into_iter()
+            .find(|metric| metric.value() == value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Settings, SpeedMetric};
+    use crate::storage::backend::MemoryBackend;
+    use crate::storage::{HistoryManager, SETTINGS_KEY, TIME_ZONE_KEY};
+    use crate::time_zone::TimeZoneChoice;
+    use serde_json::json;
+
+    #[test]
+    fn older_settings_and_time_zone_are_folded_in() {
+        let history = HistoryManager::for_profile(MemoryBackend::default(), "p");
+        history
+            .save_value(SETTINGS_KEY, &json!({ "idle_threshold_ms": 30_000 }))
+            .unwrap();
+        history
+            .save_value(TIME_ZONE_KEY, &TimeZoneChoice::Fixed(480))
+            .unwrap();
+
+        let settings = Settings::load(&history);
+        assert_eq!(settings.idle_threshold_ms, Some(30_000));
+        assert_eq!(settings.time_zone, TimeZoneChoice::Fixed(480));
+        assert!(settings.show_hints && settings.auto_advance);
+        assert_eq!(settings.daily_goal_ms(), 5 * 60_000);
+
+        let changed = Settings {
+            speed_metric: SpeedMetric::Cpm,
+            ..settings
+        };
+        changed.save(&history).unwrap();
+        assert_eq!(history.load_value::<TimeZoneChoice>(TIME_ZONE_KEY), None);
+        assert_eq!(Settings::load(&history), changed);
+        assert_eq!(SpeedMetric::Cpm.format(12.0), "60.0 CPM");
+        assert_eq!(SpeedMetric::Cpm.to_wpm(100.0), settings.speed_goal_wpm);
+    }
+}
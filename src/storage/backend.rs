@@ -0,0 +1,488 @@
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::rc::Rc;
+
+use super::is_app_key;
+use super::sync::{is_synced_key, RemoteChange, StorageNotice, SyncPing, SYNC_KEY};
+use futures_util::future::{try_join_all, LocalBoxFuture};
+use futures_util::FutureExt;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    IdbDatabase, IdbOpenDbRequest, IdbRequest, IdbTransaction, IdbTransactionMode, Storage,
+};
+
+const DATABASE_NAME: &str = "array30_typing_tutor";
+const OBJECT_STORE: &str = "entries";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum StorageError {
+    /// The browser did not expose the storage API (private mode, no window, ...).
+    Unavailable,
+    /// The backend rejected the operation, e.g. because the quota is exhausted.
+    Backend(String),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StorageError::Unavailable => write!(f, "browser storage is unavailable"),
+            StorageError::Backend(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl From<JsValue> for StorageError {
+    fn from(value: JsValue) -> Self {
+        StorageError::Backend(
+            value
+                .dyn_ref::<js_sys::Error>()
+                .map(|error| String::from(error.message()))
+                .or_else(|| value.as_string())
+                .unwrap_or_else(|| format!("{value:?}")),
+        )
+    }
+}
+
+/// String key/value persistence used by [`super::HistoryManager`].
+///
+/// Methods take `&self` so a backend can be shared between the manager and the
+/// components holding it; implementations use interior mutability where needed.
+pub trait StorageBackend {
+    fn get(&self, key: &str) -> Option<String>;
+    fn set(&self, key: &str, value: &str) -> Result<(), StorageError>;
+    fn remove(&self, key: &str);
+    /// Every key currently stored, in no particular order.
+    fn keys(&self) -> Vec<String>;
+    /// Writes that `set` and `remove` accepted but that can still fail, such as
+    /// IndexedDB transactions not yet committed. Backends that write
+    /// synchronously have none.
+    fn pending_writes(&self) -> PendingWrites {
+        PendingWrites::default()
+    }
+}
+
+/// Writes still on their way to storage; see [`StorageBackend::pending_writes`].
+#[must_use]
+#[derive(Default)]
+pub struct PendingWrites(Vec<LocalBoxFuture<'static, Result<(), StorageError>>>);
+
+impl PendingWrites {
+    pub fn new(
+        writes: impl IntoIterator<Item = LocalBoxFuture<'static, Result<(), StorageError>>>,
+    ) -> Self {
+        PendingWrites(writes.into_iter().collect())
+    }
+
+    /// Resolves once every write has been committed, or with the first failure.
+    pub async fn committed(self) -> Result<(), StorageError> {
+        try_join_all(self.0).await.map(|_| ())
+    }
+}
+
+/// Keeps everything in memory. Used directly by tests and as the read cache of
+/// [`IndexedDbBackend`]. Clones share the same entries.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryBackend {
+    entries: Rc<RefCell<BTreeMap<String, String>>>,
+}
+
+impl MemoryBackend {
+    pub fn is_empty(&self) -> bool {
+        self.entries.borrow().is_empty()
+    }
+}
+
+impl StorageBackend for MemoryBackend {
+    fn get(&self, key: &str) -> Option<String> {
+        self.entries.borrow().get(key).cloned()
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), StorageError> {
+        self.entries
+            .borrow_mut()
+            .insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) {
+        self.entries.borrow_mut().remove(key);
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.entries.borrow().keys().cloned().collect()
+    }
+}
+
+/// The browser's `localStorage`: synchronous, but limited to a few megabytes.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LocalStorageBackend;
+
+impl LocalStorageBackend {
+    fn storage(&self) -> Option<Storage> {
+        web_sys::window().and_then(|window| window.local_storage().ok().flatten())
+    }
+}
+
+impl StorageBackend for LocalStorageBackend {
+    fn get(&self, key: &str) -> Option<String> {
+        self.storage()?.get_item(key).ok().flatten()
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), StorageError> {
+        let storage = self.storage().ok_or(StorageError::Unavailable)?;
+        storage.set_item(key, value).map_err(StorageError::from)
+    }
+
+    fn remove(&self, key: &str) {
+        if let Some(storage) = self.storage() {
+            let _ = storage.remove_item(key);
+        }
+    }
+
+    fn keys(&self) -> Vec<String> {
+        let Some(storage) = self.storage() else {
+            return Vec::new();
+        };
+        let length = storage.length().unwrap_or(0);
+        (0..length)
+            .filter_map(|index| storage.key(index).ok().flatten())
+            .collect()
+    }
+}
+
+/// IndexedDB, for histories that outgrow `localStorage`.
+///
+/// IndexedDB is asynchronous, so [`IndexedDbBackend::open`] loads every entry into
+/// a [`MemoryBackend`] up front. Reads are served from that cache and writes go to
+/// the cache immediately and to the database in the background. A write whose
+/// transaction aborts is undone in the cache by reading the key back, and
+/// reported through [`StorageBackend::pending_writes`] to whoever waits on it.
+#[derive(Clone, Debug)]
+pub struct IndexedDbBackend {
+    database: IdbDatabase,
+    cache: MemoryBackend,
+    /// One promise per uncommitted transaction, by write number. Each resolves
+    /// to nothing once committed, or to the error message once aborted.
+    pending: Rc<RefCell<BTreeMap<u64, js_sys::Promise>>>,
+    next_write: Rc<Cell<u64>>,
+}
+
+impl IndexedDbBackend {
+    pub async fn open() -> Result<Self, StorageError> {
+        let factory = web_sys::window()
+            .ok_or(StorageError::Unavailable)?
+            .indexed_db()?
+            .ok_or(StorageError::Unavailable)?;
+        let request = factory.open_with_u32(DATABASE_NAME, 1)?;
+
+        let on_upgrade = Closure::<dyn FnMut(web_sys::Event)>::new(|event: web_sys::Event| {
+            let Some(request) = event
+                .target()
+                .and_then(|target| target.dyn_into::<IdbOpenDbRequest>().ok())
+            else {
+                return;
+            };
+            if let Ok(database) = request.result() {
+                let database: IdbDatabase = database.unchecked_into();
+                if !database.object_store_names().contains(OBJECT_STORE) {
+                    let _ = database.create_object_store(OBJECT_STORE);
+                }
+            }
+        });
+        request.set_onupgradeneeded(Some(on_upgrade.as_ref().unchecked_ref()));
+        let database: IdbDatabase = request_result(&request).await?.unchecked_into();
+        request.set_onupgradeneeded(None);
+
+        let store = database
+            .transaction_with_str(OBJECT_STORE)?
+            .object_store(OBJECT_STORE)?;
+        let keys: js_sys::Array = request_result(&store.get_all_keys()?)
+            .await?
+            .unchecked_into();
+        let values: js_sys::Array = request_result(&store.get_all()?).await?.unchecked_into();
+
+        let cache = MemoryBackend::default();
+        for (key, value) in keys.iter().zip(values.iter()) {
+            if let (Some(key), Some(value)) = (key.as_string(), value.as_string()) {
+                cache.set(&key, &value)?;
+            }
+        }
+
+        Ok(IndexedDbBackend {
+            database,
+            cache,
+            pending: Rc::default(),
+            next_write: Rc::default(),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    fn write(&self, key: &str, value: Option<&str>) -> Result<(), StorageError> {
+        let transaction = self
+            .database
+            .transaction_with_str_and_mode(OBJECT_STORE, IdbTransactionMode::Readwrite)?;
+        let store = transaction.object_store(OBJECT_STORE)?;
+        let ping = SyncPing::new(key);
+        let js_key = JsValue::from_str(key);
+        match value {
+            Some(value) => store.put_with_key(&JsValue::from_str(value), &js_key)?,
+            None => store.delete(&js_key)?,
+        };
+
+        let id = self.next_write.get();
+        self.next_write.set(id + 1);
+        let mut resolve = None;
+        let outcome = js_sys::Promise::new(&mut |on_resolve, _| resolve = Some(on_resolve));
+        let resolve = resolve.ok_or(StorageError::Unavailable)?;
+        let backend = self.clone();
+        let key = key.to_string();
+        // Called once, for whichever of `complete` and `abort` fires; a failed
+        // request aborts its transaction, so `abort` covers errors too.
+        let on_done = Closure::once_into_js(move |event: web_sys::Event| {
+            backend.pending.borrow_mut().remove(&id);
+            if event.type_() == "complete" {
+                // Other tabs cannot observe IndexedDB; tell them once the write is visible.
+                if let Some(ping) = ping.to_json() {
+                    let _ = LocalStorageBackend.set(SYNC_KEY, &ping);
+                }
+                let _ = resolve.call1(&JsValue::UNDEFINED, &JsValue::UNDEFINED);
+                return;
+            }
+            let message = event
+                .target()
+                .and_then(|target| target.dyn_into::<IdbTransaction>().ok())
+                .and_then(|transaction| transaction.error())
+                .map(|error| error.message())
+                .unwrap_or_else(|| "IndexedDB transaction aborted".to_string());
+            web_sys::console::warn_1(&format!("Could not save {key}: {message}").into());
+            let _ = resolve.call1(&JsValue::UNDEFINED, &JsValue::from_str(&message));
+            // The cache already holds the lost value; put back what was committed.
+            wasm_bindgen_futures::spawn_local(async move {
+                let _ = backend.refresh(&key).await;
+            });
+        });
+        transaction.set_oncomplete(Some(on_done.unchecked_ref()));
+        transaction.set_onabort(Some(on_done.unchecked_ref()));
+        self.pending.borrow_mut().insert(id, outcome);
+        Ok(())
+    }
+
+    /// Reads `key` from the database rather than from the cache, and caches it.
+    async fn refresh(&self, key: &str) -> Result<Option<String>, StorageError> {
+        let store = self
+            .database
+            .transaction_with_str(OBJECT_STORE)?
+            .object_store(OBJECT_STORE)?;
+        let value = request_result(&store.get(&JsValue::from_str(key))?)
+            .await?
+            .as_string();
+        match &value {
+            Some(value) => self.cache.set(key, value)?,
+            None => self.cache.remove(key),
+        }
+        Ok(value)
+    }
+}
+
+impl StorageBackend for IndexedDbBackend {
+    fn get(&self, key: &str) -> Option<String> {
+        self.cache.get(key)
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), StorageError> {
+        self.write(key, Some(value))?;
+        self.cache.set(key, value)
+    }
+
+    fn remove(&self, key: &str) {
+        let _ = self.write(key, None);
+        self.cache.remove(key);
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.cache.keys()
+    }
+
+    fn pending_writes(&self) -> PendingWrites {
+        PendingWrites::new(self.pending.borrow().values().map(|outcome| {
+            let outcome = JsFuture::from(outcome.clone());
+            async move {
+                match outcome.await?.as_string() {
+                    Some(message) => Err(StorageError::Backend(message)),
+                    None => Ok(()),
+                }
+            }
+            .boxed_local()
+        }))
+    }
+}
+
+/// Waits for an IndexedDB request to finish and returns its result.
+async fn request_result(request: &IdbRequest) -> Result<JsValue, StorageError> {
+    let done = js_sys::Promise::new(&mut |resolve, reject| {
+        request.set_onsuccess(Some(&resolve));
+        request.set_onerror(Some(&reject));
+    });
+    let outcome = JsFuture::from(done).await;
+    request.set_onsuccess(None);
+    request.set_onerror(None);
+    match outcome {
+        Ok(_) => Ok(request.result()?),
+        Err(_) => Err(request
+            .error()
+            .ok()
+            .flatten()
+            .map(|error| StorageError::Backend(error.message()))
+            .unwrap_or_else(|| StorageError::Backend("IndexedDB request failed".to_string()))),
+    }
+}
+
+/// The backend the app runs on: IndexedDB when the browser provides it, with
+/// `localStorage` as the fallback.
+#[derive(Clone, Debug)]
+pub enum BrowserBackend {
+    LocalStorage(LocalStorageBackend),
+    IndexedDb(IndexedDbBackend),
+}
+
+impl Default for BrowserBackend {
+    fn default() -> Self {
+        BrowserBackend::LocalStorage(LocalStorageBackend)
+    }
+}
+
+impl BrowserBackend {
+    /// Opens IndexedDB, moving the app's data kept in `localStorage` into it the
+    /// first time so sessions and profiles saved before the switch come along.
+    pub async fn open() -> Self {
+        let Ok(database) = IndexedDbBackend::open().await else {
+            return BrowserBackend::default();
+        };
+        if database.is_empty() {
+            let keys: Vec<String> = LocalStorageBackend
+                .keys()
+                .into_iter()
+                .filter(|key| is_app_key(key) && key != SYNC_KEY)
+                .collect();
+            if let Err(error) = move_keys(&LocalStorageBackend, &database, &keys).await {
+                web_sys::console::warn_1(
+                    &format!("Keeping history in localStorage: {error}").into(),
+                );
+                return BrowserBackend::default();
+            }
+        }
+        BrowserBackend::IndexedDb(database)
+    }
+
+    /// Turns a `storage` event into the change another tab made, bringing this
+    /// tab's view of the key up to date. `None` for events that are not about
+    /// this backend's data.
+    pub async fn remote_change(&self, notice: StorageNotice) -> Option<RemoteChange> {
+        match self {
+            BrowserBackend::LocalStorage(_) => is_synced_key(&notice.key).then_some(RemoteChange {
+                key: notice.key,
+                previous: notice.old_value,
+                value: notice.new_value,
+            }),
+            BrowserBackend::IndexedDb(backend) => {
+                if notice.key != SYNC_KEY {
+                    return None;
+                }
+                let ping: SyncPing = serde_json::from_str(&notice.new_value?).ok()?;
+                let previous = backend.get(&ping.key);
+                match backend.refresh(&ping.key).await {
+                    Ok(value) => Some(RemoteChange {
+                        key: ping.key,
+                        previous,
+                        value,
+                    }),
+                    Err(error) => {
+                        web_sys::console::warn_1(
+                            &format!("Could not read a change from another tab: {error}").into(),
+                        );
+                        None
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl StorageBackend for BrowserBackend {
+    fn get(&self, key: &str) -> Option<String> {
+        match self {
+            BrowserBackend::LocalStorage(backend) => backend.get(key),
+            BrowserBackend::IndexedDb(backend) => backend.get(key),
+        }
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), StorageError> {
+        match self {
+            BrowserBackend::LocalStorage(backend) => backend.set(key, value),
+            BrowserBackend::IndexedDb(backend) => backend.set(key, value),
+        }
+    }
+
+    fn remove(&self, key: &str) {
+        match self {
+            BrowserBackend::LocalStorage(backend) => backend.remove(key),
+            BrowserBackend::IndexedDb(backend) => backend.remove(key),
+        }
+    }
+
+    fn keys(&self) -> Vec<String> {
+        match self {
+            BrowserBackend::LocalStorage(backend) => backend.keys(),
+            BrowserBackend::IndexedDb(backend) => backend.keys(),
+        }
+    }
+
+    fn pending_writes(&self) -> PendingWrites {
+        match self {
+            BrowserBackend::LocalStorage(backend) => backend.pending_writes(),
+            BrowserBackend::IndexedDb(backend) => backend.pending_writes(),
+        }
+    }
+}
+
+/// Copies `keys` from `from` to `to`, and only removes them from `from` once all
+/// copies succeeded and `to` has committed them. On failure the copies are taken
+/// out of `to` again, so `from` stays the only home of the data.
+pub async fn move_keys(
+    from: &impl StorageBackend,
+    to: &impl StorageBackend,
+    keys: &[String],
+) -> Result<(), StorageError> {
+    let copied = match copy_keys(from, to, keys) {
+        Ok(()) => to.pending_writes().committed().await,
+        Err(error) => Err(error),
+    };
+    if let Err(error) = copied {
+        for key in keys {
+            to.remove(key);
+        }
+        return Err(error);
+    }
+    for key in keys {
+        from.remove(key);
+    }
+    Ok(())
+}
+
+fn copy_keys(
+    from: &impl StorageBackend,
+    to: &impl StorageBackend,
+    keys: &[String],
+) -> Result<(), StorageError> {
+    for key in keys {
+        if let Some(value) = from.get(key) {
+            to.set(key, &value)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use chrono::{Local, NaiveDateTime, Offset, TimeZone};
+use serde_json::{Map, Value};
+
+use super::SessionRecord;
+
+/// Field carrying the schema version inside every stored record. Records saved
+/// before versioning have no such field and count as version 0.
+const VERSION_FIELD: &str = "schema_version";
+
+type Fields = Map<String, Value>;
+type Migration = fn(&mut Fields) -> Result<(), String>;
+
+/// `MIGRATIONS[n]` upgrades a record from version `n` to `n + 1`.
+const MIGRATIONS: &[Migration] = &[
+    seconds_to_milliseconds,
+    add_wall_clock_time,
+    structured_timestamp,
+];
+
+pub const SCHEMA_VERSION: u64 = MIGRATIONS.len() as u64;
+
+/// Durations used to be stored as whole `elapsed_seconds`.
+fn seconds_to_milliseconds(fields: &mut Fields) -> Result<(), String> {
+    if fields.contains_key("elapsed_ms") {
+        return Ok(());
+    }
+    let seconds = fields
+        .remove("elapsed_seconds")
+        .and_then(|seconds| seconds.as_u64())
+        .ok_or("no elapsed_ms or elapsed_seconds")?;
+    fields.insert("elapsed_ms".to_string(), (seconds * 1000).into());
+    Ok(())
+}
+
+/// Before idle time was separated out, active time was the wall-clock time.
+fn add_wall_clock_time(fields: &mut Fields) -> Result<(), String> {
+    if !fields.contains_key("wall_ms") {
+        let elapsed_ms = fields.get("elapsed_ms").cloned().ok_or("no elapsed_ms")?;
+        fields.insert("wall_ms".to_string(), elapsed_ms);
+    }
+    Ok(())
+}
+
+/// Timestamps used to be local `YYYY-MM-DD HH:MM:SS` strings without an offset.
+/// The device's time zone is the best guess for where they were recorded.
+fn structured_timestamp(fields: &mut Fields) -> Result<(), String> {
+    if fields.contains_key("recorded_at") {
+        return Ok(());
+    }
+    let timestamp = fields.remove("timestamp").ok_or("no timestamp")?;
+    let text = timestamp.as_str().unwrap_or_default();
+    let local = NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S")
+        .map_err(|error| format!("unreadable timestamp {timestamp}: {error}"))?;
+    // A time skipped by a daylight saving change has no local offset of its own.
+    let offset = Local
+        .from_local_datetime(&local)
+        .earliest()
+        .map(|time| time.offset().fix())
+        .unwrap_or_else(|| Local.offset_from_utc_datetime(&local).fix());
+    let recorded_at = local
+        .and_local_timezone(offset)
+        .single()
+        .ok_or(format!("timestamp {timestamp} is out of range"))?
+        .to_utc();
+    fields.insert(
+        "recorded_at".to_string(),
+        serde_json::to_value(recorded_at).map_err(|error| error.to_string())?,
+    );
+    fields.insert(
+        "utc_offset_minutes".to_string(),
+        (offset.local_minus_utc() / 60).into(),
+    );
+    Ok(())
+}
+
+/// A stored record brought up to [`SCHEMA_VERSION`].
+pub(super) struct Upgraded {
+    pub record: SessionRecord,
+    /// Whether any migration ran, i.e. whether the stored copy should be rewritten.
+    pub changed: bool,
+}
+
+/// Parses a stored record of any known version and upgrades it.
+pub(super) fn upgrade(data: &str) -> Result<Upgraded, String> {
+    let value: Value =
+        serde_json::from_str(data).map_err(|error| format!("not valid JSON: {error}"))?;
+    upgrade_value(value)
+}
+
+/// Like [`upgrade`], for a record that is already parsed (e.g. from an export file).
+pub(super) fn upgrade_value(mut value: Value) -> Result<Upgraded, String> {
+    let fields = value
+        .as_object_mut()
+        .ok_or("not a JSON object".to_string())?;
+    let version = match fields.remove(VERSION_FIELD) {
+        None => 0,
+        Some(version) => version
+            .as_u64()
+            .ok_or(format!("invalid {VERSION_FIELD} {version}"))?,
+    };
+    if version > SCHEMA_VERSION {
+        return Err(format!(
+            "saved by a newer version of the app (schema {version}, this build reads up to {SCHEMA_VERSION})"
+        ));
+    }
+
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        migration(fields)
+            .map_err(|reason| format!("upgrade from schema {from} failed: {reason}"))?;
+    }
+    let record = serde_json::from_value(value)
+        .map_err(|error| format!("does not match schema {SCHEMA_VERSION}: {error}"))?;
+
+    Ok(Upgraded {
+        record,
+        changed: version < SCHEMA_VERSION,
+    })
+}
+
+/// Serializes a record tagged with the current [`SCHEMA_VERSION`].
+pub(super) fn to_stored_value(record: &SessionRecord) -> serde_json::Result<Value> {
+    let mut value = serde_json::to_value(record)?;
+    if let Some(fields) = value.as_object_mut() {
+        fields.insert(VERSION_FIELD.to_string(), SCHEMA_VERSION.into());
+    }
+    Ok(value)
+}
+
+/// A stored entry the startup migration could not read. The entry itself is left
+/// untouched so a later build (or a person) can still recover it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MigrationFailure {
+    pub key: String,
+    pub reason: String,
+}
+
+/// Outcome of [`super::HistoryManager::migrate`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MigrationReport {
+    /// Records rewritten at the current schema version.
+    pub migrated: usize,
+    /// Records moved from their own key into a monthly bucket.
+    pub compacted: usize,
+    pub failed: Vec<MigrationFailure>,
+}
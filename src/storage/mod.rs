@@ -0,0 +1,1025 @@
+pub mod backend;
+mod migration;
+mod profiles;
+mod remote;
+mod sync;
+mod transfer;
+mod trash;
+
+use crate::analytics::exercise_key;
+use crate::logic::{CharTiming, InputEvent, PracticeMode};
+use crate::robust_stats::OutlierRules;
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::future::Future;
+
+pub use backend::{BrowserBackend, StorageBackend, StorageError};
+pub use migration::{MigrationFailure, MigrationReport, SCHEMA_VERSION};
+pub use profiles::ProfileStore;
+pub use remote::{HttpTransport, RemoteSync, SyncReport};
+pub use sync::{listen_for_changes, StorageNotice};
+pub use transfer::{export_csv, export_json, parse_import, ImportSummary};
+pub use trash::TRASH_RETENTION_DAYS;
+
+/// The active profile's history, shared with components through context.
+pub type History = HistoryManager<BrowserBackend>;
+/// The profiles on this device, shared with components through context.
+pub type Profiles = ProfileStore<BrowserBackend>;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct SessionRecord {
+    pub wpm: f64,
+    pub accuracy: f64,
+    /// When the session was completed.
+    pub recorded_at: DateTime<Utc>,
+    /// The device's offset from UTC at that moment, in minutes (UTC+8 is 480).
+    pub utc_offset_minutes: i32,
+    /// Active typing time, with idle gaps and pauses left out.
+    pub elapsed_ms: u64,
+    /// Wall-clock time from the first keystroke to completion.
+    pub wall_ms: u64,
+    pub exercise_text: String,
+    #[serde(default)]
+    pub mode: PracticeMode,
+    #[serde(default)]
+    pub gross_wpm: f64,
+    #[serde(default)]
+    pub net_wpm: f64,
+    #[serde(default)]
+    pub corrected_errors: usize,
+    #[serde(default)]
+    pub uncorrected_errors: usize,
+    #[serde(default)]
+    pub deletions: usize,
+    #[serde(default)]
+    pub composing_ms: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<InputEvent>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub char_timings: Vec<CharTiming>,
+}
+
+/// See [`SessionRecord::identity`].
+type Identity = (DateTime<Utc>, String, u64);
+
+impl SessionRecord {
+    /// The offset the session was recorded at, for showing it on that clock.
+    pub fn recorded_offset(&self) -> FixedOffset {
+        crate::time_zone::fixed_offset(self.utc_offset_minutes)
+    }
+
+    /// What makes two records the same session when merging imports: when it was
+    /// recorded, what was typed and how long it took.
+    fn identity(&self) -> Identity {
+        (
+            self.recorded_at,
+            self.exercise_text.clone(),
+            self.elapsed_ms,
+        )
+    }
+}
+
+/// Saves and loads [`SessionRecord`]s through a [`StorageBackend`].
+///
+/// Records are grouped into one bucket per UTC month (a JSON array under
+/// `_array30_bucket_<YYYY-MM>`), with the month names listed under
+/// [`BUCKET_INDEX`], so saving a session rewrites one month rather than the
+/// whole history. Totals and per-exercise bests are kept under [`AGGREGATE`]
+/// and updated as sessions are saved, so finishing an exercise does not read
+/// every record. The Statistics tab does load them all, for its charts and
+/// outlier-aware figures.
+///
+/// Each learner profile has its own namespace of keys; see [`ProfileStore`].
+#[derive(Clone, Debug)]
+pub struct HistoryManager<B> {
+    backend: B,
+    namespace: String,
+}
+
+/// Every key the app stores starts with this, or with [`LEGACY_SESSION_PREFIX`].
+const APP_KEY_PREFIX: &str = "_array30_";
+/// Before buckets, every session had its own `session_<millis>` key, listed under
+/// [`SESSIONS_LIST`]. [`HistoryManager::compact`] folds those into buckets.
+const LEGACY_SESSION_PREFIX: &str = "session_";
+
+/// Per-profile value holding the learner's [`crate::logic::CurriculumProgress`].
+pub const PROGRESS_KEY: &str = "progress";
+/// Per-profile value holding the learner's [`crate::settings::Settings`].
+pub const SETTINGS_KEY: &str = "settings";
+/// Per-profile value that held the [`crate::time_zone::TimeZoneChoice`] before it
+/// became part of the settings; read by [`crate::settings::Settings::load`].
+pub const TIME_ZONE_KEY: &str = "time_zone";
+/// Per-profile value holding the unfinished exercise, a [`crate::logic::SessionSnapshot`].
+pub const ACTIVE_SESSION_KEY: &str = "active_session";
+/// Per-profile value holding where the history is synced to, a [`RemoteSync`].
+pub const REMOTE_SYNC_KEY: &str = "remote_sync";
+
+// Key names within a history's namespace.
+const SESSIONS_LIST: &str = "sessions_list";
+const BUCKET_INDEX: &str = "buckets";
+const BUCKET_PREFIX: &str = "bucket_";
+const AGGREGATE: &str = "aggregate";
+/// Deleted sessions kept for restoring; see [`trash::TrashEntry`].
+const TRASH: &str = "trash";
+/// Identities of sessions purged from the trash; see [`HistoryManager::purged`].
+const PURGED: &str = "purged";
+/// Schema version every stored record was last fully migrated to.
+const SCHEMA_VERSION_NAME: &str = "schema_version";
+
+impl<B: StorageBackend> HistoryManager<B> {
+    /// The history stored without a profile namespace, as it was before profiles.
+    pub fn new(backend: B) -> Self {
+        HistoryManager {
+            backend,
+            namespace: APP_KEY_PREFIX.to_string(),
+        }
+    }
+
+    pub fn for_profile(backend: B, profile_id: &str) -> Self {
+        HistoryManager {
+            backend,
+            namespace: profile_namespace(profile_id),
+        }
+    }
+
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    fn key(&self, name: &str) -> String {
+        format!("{}{name}", self.namespace)
+    }
+
+    /// Reads a small per-profile value such as settings or curriculum progress.
+    pub fn load_value<T: DeserializeOwned>(&self, name: &str) -> Option<T> {
+        let data = self.backend.get(&self.key(name))?;
+        serde_json::from_str(&data).ok()
+    }
+
+    pub fn save_value(&self, name: &str, value: &impl Serialize) -> Result<(), StorageError> {
+        self.backend.set(&self.key(name), &to_json(value)?)
+    }
+
+    pub fn remove_value(&self, name: &str) {
+        self.backend.remove(&self.key(name));
+    }
+
+    /// Resolves once the backend has committed every write made so far. When one
+    /// fails, the aggregate is dropped too, as it may count a session that was lost.
+    pub fn committed(&self) -> impl Future<Output = Result<(), StorageError>>
+    where
+        B: Clone + 'static,
+    {
+        let pending = self.backend.pending_writes();
+        let history = self.clone();
+        async move {
+            let committed = pending.committed().await;
+            if committed.is_err() {
+                history.backend.remove(&history.key(AGGREGATE));
+            }
+            committed
+        }
+    }
+
+    pub fn save_session(&self, record: SessionRecord) -> Result<(), StorageError> {
+        let month = bucket_month(&record);
+        let mut entries = self.read_bucket(&month)?;
+        entries.push(stored_value(&record)?);
+        self.write_bucket(&month, &entries)?;
+        self.add_to_index([month])?;
+        self.update_aggregate(std::slice::from_ref(&record));
+        Ok(())
+    }
+
+    /// Merges imported records into the history, skipping any already present with
+    /// the same recording time and content, and keeps each month in chronological order.
+    pub fn import_records(
+        &self,
+        records: Vec<SessionRecord>,
+    ) -> Result<ImportSummary, StorageError> {
+        let mut seen: HashSet<_> = self
+            .load_records()
+            .iter()
+            .map(SessionRecord::identity)
+            .collect();
+        let mut summary = ImportSummary::default();
+        let mut by_month: BTreeMap<String, Vec<SessionRecord>> = BTreeMap::new();
+        for record in records {
+            if seen.insert(record.identity()) {
+                by_month
+                    .entry(bucket_month(&record))
+                    .or_default()
+                    .push(record);
+            } else {
+                summary.duplicates += 1;
+            }
+        }
+
+        // Before writing, so that other tabs merging the buckets keep them.
+        let imported: Vec<SessionRecord> = by_month.values().flatten().cloned().collect();
+        self.forget_purged(&imported)?;
+
+        let mut added = Vec::new();
+        let mut written = Vec::new();
+        for (month, records) in by_month {
+            if let Err(error) = self.merge_into_bucket(&month, &records) {
+                // Keep what was written reachable; the aggregate is rebuilt later.
+                let _ = self.add_to_index(written);
+                self.backend.remove(&self.key(AGGREGATE));
+                return Err(error);
+            }
+            written.push(month);
+            added.extend(records);
+        }
+        self.add_to_index(written)?;
+        self.update_aggregate(&added);
+        summary.added = added.len();
+        Ok(summary)
+    }
+
+    fn merge_into_bucket(
+        &self,
+        month: &str,
+        records: &[SessionRecord],
+    ) -> Result<(), StorageError> {
+        let mut entries = self.read_bucket(month)?;
+        for record in records {
+            entries.push(stored_value(record)?);
+        }
+        sort_by_time(&mut entries);
+        self.write_bucket(month, &entries)
+    }
+
+    /// Every readable record, oldest first.
+    pub fn load_records(&self) -> Vec<SessionRecord> {
+        let mut records: Vec<SessionRecord> = self
+            .bucket_index()
+            .iter()
+            .filter_map(|month| self.read_bucket(month).ok())
+            .flatten()
+            .filter_map(|entry| migration::upgrade_value(entry).ok())
+            .map(|upgraded| upgraded.record)
+            .collect();
+        // Buckets written before timestamps were structured went by local months.
+        records.sort_by_key(|record| record.recorded_at);
+        records
+    }
+
+    /// Overview figures from the precomputed aggregate, rebuilt from the records
+    /// only when the aggregate is missing.
+    pub fn statistics(&self) -> Statistics {
+        if let Some(aggregate) = self.stored_aggregate() {
+            return aggregate.statistics();
+        }
+        let aggregate = self.rebuild_aggregate();
+        aggregate.statistics()
+    }
+
+    /// The fastest saved run at `exercise` among those `rules` keep. Kept in the
+    /// aggregate, so records are only read when it was dropped or the rules changed.
+    pub fn exercise_best(&self, exercise: &str, rules: &OutlierRules) -> Option<f64> {
+        let stored = self.stored_aggregate();
+        if let Some(bests) = stored
+            .as_ref()
+            .and_then(|aggregate| aggregate.exercise_bests.as_ref())
+            .filter(|bests| bests.rules == *rules)
+        {
+            return bests.wpm.get(exercise).copied();
+        }
+
+        let records = self.load_records();
+        let mut aggregate = stored.unwrap_or_else(|| Aggregate::from_records(&records));
+        let bests = ExerciseBests::from_records(&records, *rules);
+        let best = bests.wpm.get(exercise).copied();
+        aggregate.exercise_bests = Some(bests);
+        if let Ok(json) = to_json(&aggregate) {
+            let _ = self.backend.set(&self.key(AGGREGATE), &json);
+        }
+        best
+    }
+
+    /// Upgrades every stored record to [`SCHEMA_VERSION`] in place. Run at startup,
+    /// before anything is loaded, after [`HistoryManager::compact`]; records that
+    /// cannot be upgraded are kept as they are and listed in the report instead of
+    /// being dropped.
+    pub fn migrate(&self) -> MigrationReport {
+        let mut report = self.compact();
+        let up_to_date = self
+            .backend
+            .get(&self.key(SCHEMA_VERSION_NAME))
+            .and_then(|version| version.parse::<u64>().ok())
+            == Some(SCHEMA_VERSION);
+        if up_to_date && report.failed.is_empty() {
+            return report;
+        }
+
+        for month in self.bucket_index() {
+            let key = self.bucket_key(&month);
+            let mut entries = match self.read_bucket(&month) {
+                Ok(entries) => entries,
+                Err(error) => {
+                    report.failed.push(MigrationFailure {
+                        key,
+                        reason: error.to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            let mut migrated = 0;
+            for (index, entry) in entries.iter_mut().enumerate() {
+                let upgraded = migration::upgrade_value(entry.clone()).and_then(|upgraded| {
+                    if !upgraded.changed {
+                        return Ok(false);
+                    }
+                    *entry = migration::to_stored_value(&upgraded.record)
+                        .map_err(|error| error.to_string())?;
+                    Ok(true)
+                });
+                match upgraded {
+                    Ok(true) => migrated += 1,
+                    Ok(false) => {}
+                    Err(reason) => report.failed.push(MigrationFailure {
+                        key: format!("{key}#{index}"),
+                        reason,
+                    }),
+                }
+            }
+            if migrated == 0 {
+                continue;
+            }
+            match self.write_bucket(&month, &entries) {
+                Ok(()) => report.migrated += migrated,
+                Err(error) => report.failed.push(MigrationFailure {
+                    key,
+                    reason: format!("could not save the upgraded records: {error}"),
+                }),
+            }
+        }
+
+        if report.migrated > 0 || report.compacted > 0 {
+            self.rebuild_aggregate();
+        }
+        if report.failed.is_empty() {
+            let _ = self
+                .backend
+                .set(&self.key(SCHEMA_VERSION_NAME), &SCHEMA_VERSION.to_string());
+        }
+        report
+    }
+
+    /// Moves sessions still stored one key each into the monthly buckets. Entries
+    /// that cannot be read stay under their own key and stay listed.
+    pub fn compact(&self) -> MigrationReport {
+        let mut report = MigrationReport::default();
+        let Some(list) = self.backend.get(&self.key(SESSIONS_LIST)) else {
+            return report;
+        };
+        let keys: Vec<String> = match serde_json::from_str(&list) {
+            Ok(keys) => keys,
+            Err(error) => {
+                report.failed.push(MigrationFailure {
+                    key: self.key(SESSIONS_LIST),
+                    reason: format!("session list is unreadable: {error}"),
+                });
+                return report;
+            }
+        };
+
+        let mut records = Vec::new();
+        let mut moved = Vec::new();
+        let mut kept = Vec::new();
+        for key in keys {
+            let Some(data) = self.backend.get(&key) else {
+                report.failed.push(MigrationFailure {
+                    key,
+                    reason: "listed but missing from storage".to_string(),
+                });
+                continue;
+            };
+            match migration::upgrade(&data) {
+                Ok(upgraded) => {
+                    records.push(upgraded.record);
+                    moved.push(key);
+                }
+                Err(reason) => {
+                    report.failed.push(MigrationFailure {
+                        key: key.clone(),
+                        reason,
+                    });
+                    kept.push(key);
+                }
+            }
+        }
+
+        // Importing skips anything an interrupted earlier compaction already moved.
+        if let Err(error) = self.import_records(records) {
+            report.failed.push(MigrationFailure {
+                key: self.key(SESSIONS_LIST),
+                reason: format!("could not move sessions into monthly buckets: {error}"),
+            });
+            return report;
+        }
+        for key in &moved {
+            self.backend.remove(key);
+        }
+        report.compacted = moved.len();
+        if kept.is_empty() {
+            self.backend.remove(&self.key(SESSIONS_LIST));
+        } else if let Ok(list) = serde_json::to_string(&kept) {
+            let _ = self.backend.set(&self.key(SESSIONS_LIST), &list);
+        }
+        report
+    }
+
+    pub fn clear_history(&self) {
+        for key in self.stored_keys() {
+            self.backend.remove(&key);
+        }
+    }
+
+    /// Every key this history occupies in the backend.
+    pub fn stored_keys(&self) -> Vec<String> {
+        let mut keys = vec![
+            self.key(SESSIONS_LIST),
+            self.key(BUCKET_INDEX),
+            self.key(AGGREGATE),
+            self.key(SCHEMA_VERSION_NAME),
+            self.key(TRASH),
+            self.key(PURGED),
+        ];
+        if let Some(list) = self.backend.get(&self.key(SESSIONS_LIST)) {
+            keys.extend(serde_json::from_str::<Vec<String>>(&list).unwrap_or_default());
+        }
+        keys.extend(
+            self.bucket_index()
+                .iter()
+                .map(|month| self.bucket_key(month)),
+        );
+        keys.retain(|key| self.backend.get(key).is_some());
+        keys
+    }
+
+    fn bucket_key(&self, month: &str) -> String {
+        self.key(&format!("{BUCKET_PREFIX}{month}"))
+    }
+
+    fn bucket_index(&self) -> Vec<String> {
+        self.backend
+            .get(&self.key(BUCKET_INDEX))
+            .and_then(|data| serde_json::from_str::<Vec<String>>(&data).ok())
+            .unwrap_or_default()
+    }
+
+    fn add_to_index(&self, months: impl IntoIterator<Item = String>) -> Result<(), StorageError> {
+        let mut index = self.bucket_index();
+        let before = index.len();
+        for month in months {
+            if !index.contains(&month) {
+                index.push(month);
+            }
+        }
+        if index.len() == before {
+            return Ok(());
+        }
+        index.sort();
+        self.backend.set(&self.key(BUCKET_INDEX), &to_json(&index)?)
+    }
+
+    /// A missing bucket is empty; one that exists but cannot be parsed is an error,
+    /// so that it is never overwritten.
+    fn read_bucket(&self, month: &str) -> Result<Vec<Value>, StorageError> {
+        match self.backend.get(&self.bucket_key(month)) {
+            None => Ok(Vec::new()),
+            Some(data) => serde_json::from_str(&data).map_err(|error| {
+                StorageError::Backend(format!("bucket {month} is unreadable: {error}"))
+            }),
+        }
+    }
+
+    fn write_bucket(&self, month: &str, entries: &[Value]) -> Result<(), StorageError> {
+        self.backend
+            .set(&self.bucket_key(month), &to_json(entries)?)
+    }
+
+    /// The aggregate as stored, if there is a readable one.
+    fn stored_aggregate(&self) -> Option<Aggregate> {
+        let data = self.backend.get(&self.key(AGGREGATE))?;
+        serde_json::from_str(&data).ok()
+    }
+
+    /// Adds freshly stored records to the aggregate. When it cannot be updated it
+    /// is dropped instead, and [`HistoryManager::statistics`] rebuilds it.
+    fn update_aggregate(&self, added: &[SessionRecord]) {
+        let Some(mut aggregate) = self.stored_aggregate() else {
+            self.backend.remove(&self.key(AGGREGATE));
+            return;
+        };
+        added.iter().for_each(|record| aggregate.add(record));
+        let saved =
+            to_json(&aggregate).and_then(|json| self.backend.set(&self.key(AGGREGATE), &json));
+        if saved.is_err() {
+            self.backend.remove(&self.key(AGGREGATE));
+        }
+    }
+
+    fn rebuild_aggregate(&self) -> Aggregate {
+        let aggregate = Aggregate::from_records(&self.load_records());
+        if let Ok(json) = to_json(&aggregate) {
+            let _ = self.backend.set(&self.key(AGGREGATE), &json);
+        }
+        aggregate
+    }
+}
+
+/// Keys of every history and value belonging to the profile start with this.
+fn profile_namespace(profile_id: &str) -> String {
+    format!("{APP_KEY_PREFIX}profile_{profile_id}_")
+}
+
+/// Whether `key` belongs to this app rather than to something else on the origin.
+fn is_app_key(key: &str) -> bool {
+    key.starts_with(APP_KEY_PREFIX) || key.starts_with(LEGACY_SESSION_PREFIX)
+}
+
+/// `YYYY-MM` of the record's UTC time, so a bucket never depends on the time zone.
+fn bucket_month(record: &SessionRecord) -> String {
+    record.recorded_at.format("%Y-%m").to_string()
+}
+
+/// Oldest first; unreadable entries sort first, as they have no time to go by.
+fn sort_by_time(entries: &mut [Value]) {
+    entries.sort_by_cached_key(|entry| {
+        migration::upgrade_value(entry.clone())
+            .map(|upgraded| upgraded.record.recorded_at)
+            .ok()
+    });
+}
+
+fn stored_value(record: &SessionRecord) -> Result<Value, StorageError> {
+    migration::to_stored_value(record).map_err(|error| StorageError::Backend(error.to_string()))
+}
+
+fn to_json(value: &(impl Serialize + ?Sized)) -> Result<String, StorageError> {
+    serde_json::to_string(value).map_err(|error| StorageError::Backend(error.to_string()))
+}
+
+/// Running totals behind [`Statistics`] and [`HistoryManager::exercise_best`],
+/// kept up to date as sessions are saved.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+struct Aggregate {
+    sessions: usize,
+    practice_ms: u64,
+    /// Filled in by [`HistoryManager::exercise_best`] on first use.
+    #[serde(default)]
+    exercise_bests: Option<ExerciseBests>,
+}
+
+impl Aggregate {
+    fn from_records(records: &[SessionRecord]) -> Self {
+        let mut aggregate = Aggregate::default();
+        records.iter().for_each(|record| aggregate.add(record));
+        aggregate
+    }
+
+    fn add(&mut self, record: &SessionRecord) {
+        self.sessions += 1;
+        self.practice_ms += record.elapsed_ms;
+        if let Some(bests) = &mut self.exercise_bests {
+            bests.add(record);
+        }
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics {
+            total_sessions: self.sessions,
+            total_practice_ms: self.practice_ms,
+        }
+    }
+}
+
+/// Fastest speed on each exercise among the sessions `rules` keep, so a pasted
+/// run does not stand as the best to beat.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+struct ExerciseBests {
+    rules: OutlierRules,
+    wpm: BTreeMap<String, f64>,
+}
+
+impl ExerciseBests {
+    fn from_records(records: &[SessionRecord], rules: OutlierRules) -> Self {
+        let mut bests = ExerciseBests {
+            rules,
+            wpm: BTreeMap::new(),
+        };
+        records.iter().for_each(|record| bests.add(record));
+        bests
+    }
+
+    fn add(&mut self, record: &SessionRecord) {
+        if self.rules.rejects(record).is_some() {
+            return;
+        }
+        let best = self
+            .wpm
+            .entry(exercise_key(record).to_string())
+            .or_insert(record.wpm);
+        *best = best.max(record.wpm);
+    }
+}
+
+/// Totals over every saved session, outliers included. Speed and accuracy
+/// figures leave outliers out, so they come from
+/// [`RobustStatistics`](crate::robust_stats::RobustStatistics) instead.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Statistics {
+    pub total_sessions: usize,
+    pub total_practice_ms: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::backend::{move_keys, MemoryBackend, PendingWrites};
+    use super::{
+        migration, Aggregate, HistoryManager, SessionRecord, Statistics, StorageBackend,
+        StorageError, SCHEMA_VERSION,
+    };
+    use crate::logic::{CharTiming, InputEvent, PracticeMode};
+    use crate::robust_stats::OutlierRules;
+    use chrono::{DateTime, NaiveDateTime, Utc};
+    use futures_util::FutureExt;
+
+    #[test]
+    fn statistics_empty_records_returns_default() {
+        let stats = Aggregate::from_records(&[]).statistics();
+        assert_eq!(stats, Statistics::default());
+    }
+
+    #[test]
+    fn statistics_from_records_aggregates_fields() {
+        let records = vec![
+            SessionRecord {
+                wpm: 20.0,
+                accuracy: 95.0,
+                elapsed_ms: 30_000,
+                exercise_text: "練習一".to_string(),
+                ..SessionRecord::default()
+            },
+            SessionRecord {
+                wpm: 40.0,
+                accuracy: 90.0,
+                elapsed_ms: 45_500,
+                exercise_text: "練習二".to_string(),
+                ..SessionRecord::default()
+            },
+        ];
+
+        let stats = Aggregate::from_records(&records).statistics();
+
+        assert_eq!(stats.total_sessions, 2);
+        assert_eq!(stats.total_practice_ms, 75_500);
+    }
+
+    #[test]
+    fn legacy_record_with_whole_seconds_still_loads() {
+        let data = r#"{"wpm":12.0,"accuracy":100.0,"timestamp":"2026-01-01 10:00:00","elapsed_seconds":3,"exercise_text":"練習"}"#;
+
+        let record = migration::upgrade(data)
+            .expect("legacy record should parse")
+            .record;
+
+        assert_eq!(record.elapsed_ms, 3000);
+        assert_eq!(record.wall_ms, 3000);
+        // Read as the device's local time, so it shows as written on its own clock.
+        assert_eq!(
+            record
+                .recorded_at
+                .with_timezone(&record.recorded_offset())
+                .naive_local(),
+            at("2026-01-01 10:00:00").naive_utc()
+        );
+        assert!(migration::upgrade(&data.replace("2026-01-01 10:00:00", "t")).is_err());
+    }
+
+    #[test]
+    fn record_round_trips_milliseconds_and_events() {
+        let record = SessionRecord {
+            wpm: 13.3,
+            accuracy: 100.0,
+            recorded_at: at("2026-01-01 02:00:00"),
+            utc_offset_minutes: 480,
+            elapsed_ms: 900,
+            wall_ms: 15_900,
+            exercise_text: "字".to_string(),
+            mode: PracticeMode::Drill,
+            gross_wpm: 26.6,
+            net_wpm: 13.3,
+            corrected_errors: 1,
+            uncorrected_errors: 0,
+            deletions: 1,
+            composing_ms: 400,
+            events: vec![InputEvent {
+                at_ms: 0,
+                inserted: "字".to_string(),
+                ..InputEvent::default()
+            }],
+            char_timings: vec![CharTiming {
+                ch: '字',
+                latency_ms: Some(900),
+                errors: 0,
+                mistyped: String::new(),
+            }],
+        };
+
+        let stored = migration::to_stored_value(&record).unwrap();
+        let upgraded = migration::upgrade(&stored.to_string()).unwrap();
+
+        assert!(!upgraded.changed);
+        assert_eq!(upgraded.record, record);
+    }
+
+    /// A UTC time written the way legacy timestamps were.
+    fn at(timestamp: &str) -> DateTime<Utc> {
+        NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S")
+            .unwrap()
+            .and_utc()
+    }
+
+    fn record(timestamp: &str, text: &str) -> SessionRecord {
+        SessionRecord {
+            wpm: 10.0,
+            recorded_at: at(timestamp),
+            exercise_text: text.to_string(),
+            ..SessionRecord::default()
+        }
+    }
+
+    /// Stores `entries` the way builds before monthly buckets did.
+    fn store_legacy(backend: &MemoryBackend, entries: &[(&str, &str)]) {
+        let keys: Vec<&str> = entries.iter().map(|(key, _)| *key).collect();
+        backend
+            .set(
+                "_array30_sessions_list",
+                &serde_json::to_string(&keys).unwrap(),
+            )
+            .unwrap();
+        for (key, data) in entries {
+            if !data.is_empty() {
+                backend.set(key, data).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn history_manager_saves_loads_and_clears_records() {
+        let backend = MemoryBackend::default();
+        let history = HistoryManager::new(backend.clone());
+        let january = record("2026-01-31 20:00:00", "一");
+        let february = record("2026-02-01 08:00:00", "二");
+        // Already February in Taipei, still January in UTC.
+        let taipei = SessionRecord {
+            utc_offset_minutes: 480,
+            ..record("2026-01-31 21:00:00", "三")
+        };
+
+        history.save_session(february.clone()).unwrap();
+        history.save_session(taipei.clone()).unwrap();
+        history.save_session(january.clone()).unwrap();
+
+        assert_eq!(history.load_records(), [january, taipei, february]);
+        assert_eq!(history.statistics().total_sessions, 3);
+        assert!(backend
+            .get("_array30_bucket_2026-01")
+            .unwrap()
+            .contains("三"));
+
+        history.clear_history();
+        assert!(history.load_records().is_empty());
+        assert!(backend.is_empty());
+    }
+
+    #[test]
+    fn statistics_come_from_the_aggregate_and_rebuild_when_missing() {
+        let backend = MemoryBackend::default();
+        let history = HistoryManager::new(backend.clone());
+        history
+            .save_session(record("2026-01-01 10:00:00", "一"))
+            .unwrap();
+        let aggregate = history.statistics();
+        history
+            .save_session(record("2026-01-02 10:00:00", "二"))
+            .unwrap();
+
+        assert_eq!(aggregate.total_sessions, 1);
+        assert_eq!(history.statistics().total_sessions, 2);
+        backend.remove("_array30_aggregate");
+        assert_eq!(history.statistics().total_sessions, 2);
+    }
+
+    #[test]
+    fn exercise_bests_follow_the_outlier_rules() {
+        let backend = MemoryBackend::default();
+        let history = HistoryManager::new(backend.clone());
+        let attempt = |timestamp, wpm| SessionRecord {
+            wpm,
+            ..record(timestamp, "天地玄黃")
+        };
+        history
+            .save_session(attempt("2026-01-01 10:00:00", 30.0))
+            .unwrap();
+        history
+            .save_session(attempt("2026-01-02 10:00:00", 900.0))
+            .unwrap();
+        let rules = OutlierRules::default();
+        let keep_all = OutlierRules {
+            max_wpm: None,
+            min_characters: 0,
+        };
+
+        assert_eq!(history.exercise_best("天地玄黃", &rules), Some(30.0));
+        assert_eq!(history.exercise_best("宇宙洪荒", &rules), None);
+        assert_eq!(history.exercise_best("天地玄黃", &keep_all), Some(900.0));
+        assert_eq!(history.exercise_best("天地玄黃", &rules), Some(30.0));
+
+        // Later sessions update the stored bests without the records being read.
+        history
+            .save_session(attempt("2026-01-03 10:00:00", 35.0))
+            .unwrap();
+        backend.remove("_array30_bucket_2026-01");
+        assert_eq!(history.exercise_best("天地玄黃", &rules), Some(35.0));
+    }
+
+    #[test]
+    fn move_keys_copies_history_between_backends() {
+        let from = MemoryBackend::default();
+        let history = HistoryManager::new(from.clone());
+        history
+            .save_session(record("2026-01-01 10:00:00", "一"))
+            .unwrap();
+        let to = MemoryBackend::default();
+
+        move_keys(&from, &to, &history.stored_keys())
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+
+        assert!(from.is_empty());
+        assert_eq!(
+            HistoryManager::new(to).load_records(),
+            [record("2026-01-01 10:00:00", "一")]
+        );
+    }
+
+    /// Accepts every write and then fails to commit it, like an IndexedDB
+    /// transaction aborted over quota.
+    #[derive(Default)]
+    struct AbortingBackend(MemoryBackend);
+
+    impl StorageBackend for AbortingBackend {
+        fn get(&self, key: &str) -> Option<String> {
+            self.0.get(key)
+        }
+
+        fn set(&self, key: &str, value: &str) -> Result<(), StorageError> {
+            self.0.set(key, value)
+        }
+
+        fn remove(&self, key: &str) {
+            self.0.remove(key);
+        }
+
+        fn keys(&self) -> Vec<String> {
+            self.0.keys()
+        }
+
+        fn pending_writes(&self) -> PendingWrites {
+            let aborted = async { Err(StorageError::Backend("QuotaExceededError".to_string())) };
+            PendingWrites::new([aborted.boxed_local()])
+        }
+    }
+
+    #[test]
+    fn move_keys_keeps_the_source_when_the_copy_is_not_committed() {
+        let from = MemoryBackend::default();
+        let history = HistoryManager::new(from.clone());
+        history
+            .save_session(record("2026-01-01 10:00:00", "一"))
+            .unwrap();
+        let keys_before = from.keys();
+        let to = AbortingBackend::default();
+
+        let moved = move_keys(&from, &to, &history.stored_keys())
+            .now_or_never()
+            .unwrap();
+
+        assert_eq!(
+            moved,
+            Err(StorageError::Backend("QuotaExceededError".to_string()))
+        );
+        assert_eq!(from.keys(), keys_before);
+        assert!(to.0.is_empty());
+        assert_eq!(
+            history.load_records(),
+            [record("2026-01-01 10:00:00", "一")]
+        );
+    }
+
+    #[test]
+    fn migrate_compacts_legacy_keys_and_reports_unreadable_records() {
+        let backend = MemoryBackend::default();
+        let history = HistoryManager::new(backend.clone());
+        history
+            .save_session(record("2026-01-05 10:00:00", "零"))
+            .unwrap();
+        store_legacy(
+            &backend,
+            &[
+                (
+                    "session_1",
+                    r#"{"wpm":1.0,"accuracy":90.0,"timestamp":"2026-01-01 09:00:00","elapsed_seconds":2,"exercise_text":"一"}"#,
+                ),
+                ("session_2", r#"{"wpm":1.0,"timestamp":""}"#),
+                ("session_3", r#"{"schema_version":99}"#),
+                ("session_4", ""),
+            ],
+        );
+
+        let report = history.migrate();
+
+        assert_eq!(report.compacted, 1);
+        let failed: Vec<&str> = report.failed.iter().map(|f| f.key.as_str()).collect();
+        assert_eq!(failed, ["session_2", "session_3", "session_4"]);
+        assert!(backend.get("session_1").is_none());
+        assert_eq!(
+            backend.get("session_2").unwrap(),
+            r#"{"wpm":1.0,"timestamp":""}"#
+        );
+        let records = history.load_records();
+        let texts: Vec<&str> = records.iter().map(|r| r.exercise_text.as_str()).collect();
+        assert_eq!(texts, ["一", "零"]);
+        assert_eq!(records[0].elapsed_ms, 2000);
+        assert_eq!(history.statistics().total_sessions, 2);
+        let bucket = backend.get("_array30_bucket_2026-01").unwrap();
+        assert!(bucket.contains(&format!(r#""schema_version":{SCHEMA_VERSION}"#)));
+
+        let again: Vec<String> = history
+            .migrate()
+            .failed
+            .into_iter()
+            .map(|f| f.key)
+            .collect();
+        assert_eq!(again, ["session_2", "session_3"]);
+    }
+
+    #[test]
+    fn migrate_upgrades_records_inside_buckets() {
+        let backend = MemoryBackend::default();
+        let history = HistoryManager::new(backend.clone());
+        backend.set("_array30_buckets", r#"["2026-01"]"#).unwrap();
+        backend
+            .set(
+                "_array30_bucket_2026-01",
+                r#"[{"wpm":1.0,"accuracy":90.0,"timestamp":"2026-01-01 09:00:00","elapsed_seconds":2,"exercise_text":"一"},{"bad":true}]"#,
+            )
+            .unwrap();
+
+        let report = history.migrate();
+
+        assert_eq!(report.migrated, 1);
+        assert_eq!(report.failed[0].key, "_array30_bucket_2026-01#1");
+        assert_eq!(history.load_records()[0].wall_ms, 2000);
+        assert!(backend
+            .get("_array30_bucket_2026-01")
+            .unwrap()
+            .contains(r#""bad":true"#));
+    }
+
+    #[test]
+    fn import_records_merges_without_duplicates_in_time_order() {
+        let history = HistoryManager::new(MemoryBackend::default());
+        history
+            .save_session(record("2026-01-02 09:00:00", "二"))
+            .unwrap();
+
+        let summary = history
+            .import_records(vec![
+                record("2026-01-03 09:00:00", "三"),
+                record("2026-01-02 09:00:00", "二"),
+                record("2026-01-01 09:00:00", "一"),
+                record("2026-01-01 09:00:00", "一"),
+            ])
+            .unwrap();
+
+        assert_eq!(summary.added, 2);
+        assert_eq!(summary.duplicates, 2);
+        let texts: Vec<String> = history
+            .load_records()
+            .into_iter()
+            .map(|record| record.exercise_text)
+            .collect();
+        assert_eq!(texts, ["一", "二", "三"]);
+        assert_eq!(history.statistics().total_sessions, 3);
+    }
+}
@@ -0,0 +1,226 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    is_app_key, profile_namespace, to_json, HistoryManager, StorageBackend, StorageError,
+    APP_KEY_PREFIX,
+};
+
+const PROFILES_KEY: &str = "_array30_profiles";
+const ACTIVE_PROFILE_KEY: &str = "_array30_active_profile";
+/// Profile that data from before profiles existed is moved into.
+pub const DEFAULT_PROFILE_ID: &str = "default";
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+}
+
+impl Profile {
+    fn default_profile() -> Self {
+        Profile {
+            id: DEFAULT_PROFILE_ID.to_string(),
+            name: "Default".to_string(),
+        }
+    }
+}
+
+/// The learner profiles on this device and which one is active. Sessions,
+/// settings and curriculum progress live in each profile's own key namespace.
+#[derive(Clone, Debug)]
+pub struct ProfileStore<B> {
+    backend: B,
+}
+
+impl<B: StorageBackend + Clone> ProfileStore<B> {
+    pub fn new(backend: B) -> Self {
+        ProfileStore { backend }
+    }
+
+    /// Always at least one profile; before any is saved that is the default one.
+    pub fn profiles(&self) -> Vec<Profile> {
+        self.backend
+            .get(PROFILES_KEY)
+            .and_then(|data| serde_json::from_str::<Vec<Profile>>(&data).ok())
+            .filter(|profiles| !profiles.is_empty())
+            .unwrap_or_else(|| vec![Profile::default_profile()])
+    }
+
+    pub fn active(&self) -> Profile {
+        let profiles = self.profiles();
+        let active_id = self.backend.get(ACTIVE_PROFILE_KEY);
+        profiles
+            .iter()
+            .find(|profile| Some(&profile.id) == active_id.as_ref())
+            .unwrap_or(&profiles[0])
+            .clone()
+    }
+
+    /// History, settings and progress of the active profile.
+    pub fn history(&self) -> HistoryManager<B> {
+        HistoryManager::for_profile(self.backend.clone(), &self.active().id)
+    }
+
+    /// Creates the default profile on first run, moving everything stored before
+    /// profiles existed into its namespace.
+    pub fn ensure_default(&self) -> Result<(), StorageError> {
+        if self.backend.get(PROFILES_KEY).is_some() {
+            return Ok(());
+        }
+
+        let target = profile_namespace(DEFAULT_PROFILE_ID);
+        let legacy = HistoryManager::new(self.backend.clone());
+        let moves: Vec<(String, String)> = legacy
+            .stored_keys()
+            .into_iter()
+            // One-key-per-session entries keep their names; the moved list still finds them.
+            .filter_map(|key| {
+                let renamed = format!("{target}{}", key.strip_prefix(APP_KEY_PREFIX)?);
+                Some((key, renamed))
+            })
+            .collect();
+        for (from, to) in &moves {
+            if let Some(value) = self.backend.get(from) {
+                self.backend.set(to, &value)?;
+            }
+        }
+        self.save_profiles(&[Profile::default_profile()])?;
+        for (from, _) in &moves {
+            self.backend.remove(from);
+        }
+        Ok(())
+    }
+
+    pub fn create(&self, name: &str) -> Result<Profile, StorageError> {
+        let mut profiles = self.profiles();
+        let mut millis = chrono::Utc::now().timestamp_millis();
+        while profiles
+            .iter()
+            .any(|profile| profile.id == format!("p{millis}"))
+        {
+            millis += 1;
+        }
+        let profile = Profile {
+            id: format!("p{millis}"),
+            name: clean_name(name, profiles.len() + 1),
+        };
+        profiles.push(profile.clone());
+        self.save_profiles(&profiles)?;
+        Ok(profile)
+    }
+
+    pub fn rename(&self, id: &str, name: &str) -> Result<(), StorageError> {
+        let mut profiles = self.profiles();
+        if let Some(index) = profiles.iter().position(|profile| profile.id == id) {
+            profiles[index].name = clean_name(name, index + 1);
+            self.save_profiles(&profiles)?;
+        }
+        Ok(())
+    }
+
+    pub fn switch(&self, id: &str) -> Result<(), StorageError> {
+        self.backend.set(ACTIVE_PROFILE_KEY, id)
+    }
+
+    /// Removes a profile and everything stored under it. The last profile cannot be
+    /// deleted; deleting the active one switches to the first remaining profile.
+    pub fn delete(&self, id: &str) -> Result<(), StorageError> {
+        let mut profiles = self.profiles();
+        if profiles.len() <= 1 {
+            return Ok(());
+        }
+        profiles.retain(|profile| profile.id != id);
+        self.save_profiles(&profiles)?;
+        if self.backend.get(ACTIVE_PROFILE_KEY).as_deref() == Some(id) {
+            self.switch(&profiles[0].id)?;
+        }
+
+        HistoryManager::for_profile(self.backend.clone(), id).clear_history();
+        let namespace = profile_namespace(id);
+        for key in self.backend.keys() {
+            if is_app_key(&key) && key.starts_with(&namespace) {
+                self.backend.remove(&key);
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `key` holds the list of profiles, e.g. when another tab changed it.
+    pub fn is_profile_list(key: &str) -> bool {
+        key == PROFILES_KEY
+    }
+
+    fn save_profiles(&self, profiles: &[Profile]) -> Result<(), StorageError> {
+        self.backend.set(PROFILES_KEY, &to_json(profiles)?)
+    }
+}
+
+fn clean_name(name: &str, number: usize) -> String {
+    match name.trim() {
+        "" => format!("Learner {number}"),
+        name => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ProfileStore, DEFAULT_PROFILE_ID};
+    use crate::storage::backend::MemoryBackend;
+    use crate::storage::{HistoryManager, SessionRecord, StorageBackend};
+    use chrono::{TimeZone, Utc};
+
+    fn record(text: &str) -> SessionRecord {
+        SessionRecord {
+            recorded_at: Utc.with_ymd_and_hms(2026, 3, 1, 2, 0, 0).unwrap(),
+            utc_offset_minutes: 480,
+            exercise_text: text.to_string(),
+            ..SessionRecord::default()
+        }
+    }
+
+    #[test]
+    fn existing_history_moves_into_the_default_profile() {
+        let backend = MemoryBackend::default();
+        HistoryManager::new(backend.clone())
+            .save_session(record("舊"))
+            .unwrap();
+        let store = ProfileStore::new(backend.clone());
+
+        store.ensure_default().unwrap();
+        store.ensure_default().unwrap();
+
+        assert_eq!(store.active().id, DEFAULT_PROFILE_ID);
+        assert_eq!(store.history().load_records(), [record("舊")]);
+        assert!(HistoryManager::new(backend.clone())
+            .load_records()
+            .is_empty());
+        assert!(backend.get("_array30_buckets").is_none());
+    }
+
+    #[test]
+    fn profiles_keep_separate_histories_and_values() {
+        let backend = MemoryBackend::default();
+        let store = ProfileStore::new(backend.clone());
+        store.ensure_default().unwrap();
+        store.history().save_session(record("一")).unwrap();
+
+        let second = store.create("  ").unwrap();
+        assert_eq!(second.name, "Learner 2");
+        store.rename(&second.id, "Mei").unwrap();
+        store.switch(&second.id).unwrap();
+        store.history().save_session(record("二")).unwrap();
+        store.history().save_value("progress", &3).unwrap();
+
+        assert_eq!(store.active().name, "Mei");
+        assert_eq!(store.history().load_records(), [record("二")]);
+        assert_eq!(store.history().load_value::<usize>("progress"), Some(3));
+
+        store.delete(&second.id).unwrap();
+        assert_eq!(store.active().id, DEFAULT_PROFILE_ID);
+        assert_eq!(store.history().load_records(), [record("一")]);
+        assert!(backend.keys().iter().all(|key| !key.contains(&second.id)));
+
+        store.delete(DEFAULT_PROFILE_ID).unwrap();
+        assert_eq!(store.profiles().len(), 1);
+    }
+}
@@ -0,0 +1,345 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use array30_sync_protocol::{
+    changes_path, is_valid_space, PullResponse, PushRequest, PushResponse, SyncState, BATCH_SIZE,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Headers, Request, RequestInit, Response};
+
+use super::{
+    migration, stored_value, to_json, HistoryManager, SessionRecord, StorageBackend, StorageError,
+    REMOTE_SYNC_KEY,
+};
+
+/// Where a profile's history is synced to, and how far syncing got.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct RemoteSync {
+    /// Base URL of the sync server, such as `http://localhost:8787`.
+    pub server_url: String,
+    /// Name shared by every device syncing this history.
+    pub space: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub token: String,
+    #[serde(default)]
+    pub last_synced_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    state: SyncState,
+}
+
+impl RemoteSync {
+    pub fn is_configured(&self) -> bool {
+        !self.server_url.is_empty() && is_valid_space(&self.space)
+    }
+
+    /// Checks new settings. Another server or space starts over, offering it the
+    /// whole history.
+    pub fn configure(&self, server_url: &str, space: &str, token: &str) -> Result<Self, String> {
+        let server_url = server_url.trim().trim_end_matches('/');
+        let space = space.trim();
+        if !(server_url.starts_with("http://") || server_url.starts_with("https://")) {
+            return Err("The server address starts with http:// or https://.".to_string());
+        }
+        if !is_valid_space(space) {
+            return Err(
+                "The space name is made of letters, digits, - and _, up to 64 of them.".to_string(),
+            );
+        }
+        let same = server_url == self.server_url && space == self.space;
+        Ok(RemoteSync {
+            server_url: server_url.to_string(),
+            space: space.to_string(),
+            token: token.trim().to_string(),
+            last_synced_at: self.last_synced_at.filter(|_| same),
+            state: if same {
+                self.state.clone()
+            } else {
+                SyncState::default()
+            },
+        })
+    }
+}
+
+/// What one sync changed.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SyncReport {
+    /// Sessions from other devices added here.
+    pub added: usize,
+    /// Sessions deleted on other devices and moved to the trash here.
+    pub deleted: usize,
+    /// Additions and deletions sent to the server.
+    pub pushed: usize,
+    /// Sessions from the server this version of the app could not read.
+    pub unreadable: usize,
+}
+
+/// How [`HistoryManager::sync_remote`] reaches the server.
+#[allow(async_fn_in_trait)]
+pub trait SyncTransport {
+    async fn pull(&self, since: u64) -> Result<PullResponse, StorageError>;
+    async fn push(&self, request: &PushRequest) -> Result<PushResponse, StorageError>;
+}
+
+/// The sync server over `fetch`.
+pub struct HttpTransport {
+    url: String,
+    token: String,
+}
+
+impl HttpTransport {
+    pub fn new(remote: &RemoteSync) -> Self {
+        HttpTransport {
+            url: format!("{}{}", remote.server_url, changes_path(&remote.space)),
+            token: remote.token.clone(),
+        }
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        body: Option<String>,
+    ) -> Result<T, StorageError> {
+        let window = web_sys::window().ok_or(StorageError::Unavailable)?;
+        let headers = Headers::new()?;
+        if !self.token.is_empty() {
+            headers.set("Authorization", &format!("Bearer {}", self.token))?;
+        }
+        let init = RequestInit::new();
+        if let Some(body) = body {
+            headers.set("Content-Type", "application/json")?;
+            init.set_method("POST");
+            init.set_body(&JsValue::from_str(&body));
+        }
+        init.set_headers(&headers);
+        let request = Request::new_with_str_and_init(url, &init)?;
+
+        let response: Response = JsFuture::from(window.fetch_with_request(&request))
+            .await
+            .map_err(|_| StorageError::Backend(format!("the sync server at {url} did not answer")))?
+            .unchecked_into();
+        let text = JsFuture::from(response.text()?)
+            .await?
+            .as_string()
+            .unwrap_or_default();
+        if !response.ok() {
+            return Err(StorageError::Backend(format!(
+                "the sync server answered {}: {text}",
+                response.status()
+            )));
+        }
+        serde_json::from_str(&text).map_err(|error| {
+            StorageError::Backend(format!(
+                "the sync server sent something unexpected: {error}"
+            ))
+        })
+    }
+}
+
+impl SyncTransport for HttpTransport {
+    async fn pull(&self, since: u64) -> Result<PullResponse, StorageError> {
+        self.send(&format!("{}?since={since}", self.url), None)
+            .await
+    }
+
+    async fn push(&self, request: &PushRequest) -> Result<PushResponse, StorageError> {
+        self.send(&self.url, Some(to_json(request)?)).await
+    }
+}
+
+impl SessionRecord {
+    /// The session's id on every device, built from [`SessionRecord::identity`].
+    fn sync_id(&self) -> String {
+        // FNV-1a, which unlike the standard hasher is the same on every build.
+        let text_hash = self
+            .exercise_text
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+            });
+        format!(
+            "{}-{}-{text_hash:016x}",
+            self.recorded_at
+                .to_rfc3339_opts(SecondsFormat::AutoSi, true),
+            self.elapsed_ms
+        )
+    }
+}
+
+impl<B: StorageBackend> HistoryManager<B> {
+    /// Pulls what other devices changed since the last sync into this history,
+    /// then pushes what changed here. Progress is saved after every step, so an
+    /// interrupted sync picks up where it stopped.
+    pub async fn sync_remote(
+        &self,
+        transport: &impl SyncTransport,
+        now: DateTime<Utc>,
+    ) -> Result<SyncReport, StorageError> {
+        let mut remote: RemoteSync = self.load_value(REMOTE_SYNC_KEY).unwrap_or_default();
+        let mut report = SyncReport::default();
+
+        loop {
+            let pull = transport.pull(remote.state.cursor).await?;
+            let more = pull.more;
+            let local = self.sessions_by_id();
+            let ids: BTreeSet<String> = local.keys().cloned().collect();
+            let incoming = remote.state.apply(pull, &ids);
+
+            let mut added = Vec::new();
+            for value in incoming.added.into_values() {
+                match migration::upgrade_value(value) {
+                    Ok(upgraded) => added.push(upgraded.record),
+                    Err(_) => report.unreadable += 1,
+                }
+            }
+            report.added += self.import_records(added)?.added;
+            for id in &incoming.deleted {
+                if let Some(record) = local.get(id) {
+                    if self.delete_session(record, now)? {
+                        report.deleted += 1;
+                    }
+                }
+            }
+            self.save_value(REMOTE_SYNC_KEY, &remote)?;
+            if !more {
+                break;
+            }
+        }
+
+        let local = self
+            .sessions_by_id()
+            .into_iter()
+            .map(|(id, record)| Ok((id, stored_value(&record)?)))
+            .collect::<Result<BTreeMap<_, _>, StorageError>>()?;
+        for batch in remote.state.pending(&local).chunks(BATCH_SIZE) {
+            transport
+                .push(&PushRequest {
+                    changes: batch.to_vec(),
+                })
+                .await?;
+            remote.state.pushed(batch);
+            self.save_value(REMOTE_SYNC_KEY, &remote)?;
+            report.pushed += batch.len();
+        }
+
+        remote.last_synced_at = Some(now);
+        self.save_value(REMOTE_SYNC_KEY, &remote)?;
+        Ok(report)
+    }
+
+    fn sessions_by_id(&self) -> BTreeMap<String, SessionRecord> {
+        self.load_records()
+            .into_iter()
+            .map(|record| (record.sync_id(), record))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RemoteSync, SyncTransport};
+    use crate::storage::backend::MemoryBackend;
+    use crate::storage::{HistoryManager, SessionRecord, StorageError, REMOTE_SYNC_KEY};
+    use array30_sync_protocol::{Log, PullResponse, PushRequest, PushResponse};
+    use chrono::{TimeZone, Utc};
+    use futures_util::FutureExt;
+    use std::cell::RefCell;
+
+    /// The server's log, without the HTTP in between.
+    #[derive(Default)]
+    struct InMemoryServer(RefCell<Log>);
+
+    impl SyncTransport for InMemoryServer {
+        async fn pull(&self, since: u64) -> Result<PullResponse, StorageError> {
+            Ok(self.0.borrow().since(since))
+        }
+
+        async fn push(&self, request: &PushRequest) -> Result<PushResponse, StorageError> {
+            let appended = self.0.borrow_mut().append(request.changes.clone());
+            Ok(PushResponse {
+                appended: appended.len(),
+            })
+        }
+    }
+
+    fn record(day: u32, text: &str) -> SessionRecord {
+        SessionRecord {
+            wpm: 10.0,
+            recorded_at: Utc.with_ymd_and_hms(2026, 3, day, 9, 0, 0).unwrap(),
+            exercise_text: text.to_string(),
+            ..SessionRecord::default()
+        }
+    }
+
+    fn device() -> HistoryManager<MemoryBackend> {
+        let history = HistoryManager::for_profile(MemoryBackend::default(), "p");
+        let remote = RemoteSync::default()
+            .configure("http://localhost:8787/", "home", "")
+            .unwrap();
+        history.save_value(REMOTE_SYNC_KEY, &remote).unwrap();
+        history
+    }
+
+    #[test]
+    fn sessions_and_deletions_reach_the_other_device() {
+        let server = InMemoryServer::default();
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        let sync = |history: &HistoryManager<MemoryBackend>| {
+            history
+                .sync_remote(&server, now)
+                .now_or_never()
+                .expect("the in-memory server answers at once")
+                .unwrap()
+        };
+        let phone = device();
+        let laptop = device();
+        phone.save_session(record(1, "一")).unwrap();
+        phone.save_session(record(2, "二")).unwrap();
+        laptop.save_session(record(3, "三")).unwrap();
+
+        assert_eq!(sync(&phone).pushed, 2);
+        let report = sync(&laptop);
+        assert_eq!((report.added, report.pushed), (2, 1));
+        assert_eq!(sync(&phone).added, 1);
+        assert_eq!(phone.load_records(), laptop.load_records());
+
+        laptop.delete_session(&record(1, "一"), now).unwrap();
+        assert_eq!(sync(&laptop).pushed, 1);
+        assert_eq!(sync(&phone).deleted, 1);
+        assert_eq!(phone.load_records(), [record(2, "二"), record(3, "三")]);
+        assert_eq!(phone.trash()[0].records(), [record(1, "一")]);
+        assert_eq!(sync(&phone), Default::default());
+    }
+
+    #[test]
+    fn another_space_starts_over() {
+        let remote = RemoteSync {
+            state: array30_sync_protocol::SyncState {
+                cursor: 5,
+                ..Default::default()
+            },
+            ..RemoteSync::default()
+                .configure("https://sync.example", "home", "t")
+                .unwrap()
+        };
+        assert_eq!(
+            remote
+                .configure("https://sync.example/", "home", "t2")
+                .unwrap()
+                .state
+                .cursor,
+            5
+        );
+        assert_eq!(
+            remote
+                .configure("https://sync.example", "work", "t")
+                .unwrap()
+                .state
+                .cursor,
+            0
+        );
+        assert!(remote.configure("sync.example", "home", "").is_err());
+        assert!(remote.configure("https://sync.example", "a/b", "").is_err());
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::StorageEvent;
+
+use super::{
+    migration, sort_by_time, to_json, HistoryManager, Identity, SessionRecord, StorageBackend,
+    AGGREGATE, APP_KEY_PREFIX, BUCKET_PREFIX,
+};
+
+/// `localStorage` key an IndexedDB-backed tab touches after each write, so that
+/// other tabs get a `storage` event naming the key that changed.
+pub(super) const SYNC_KEY: &str = "_array30_sync";
+
+/// Written under [`SYNC_KEY`]. The nonce makes every ping a change, as the
+/// browser only fires `storage` events when the value differs.
+#[derive(Serialize, Deserialize)]
+pub(super) struct SyncPing {
+    pub key: String,
+    pub nonce: f64,
+}
+
+impl SyncPing {
+    pub fn new(key: &str) -> Self {
+        SyncPing {
+            key: key.to_string(),
+            nonce: js_sys::Math::random(),
+        }
+    }
+
+    pub fn to_json(&self) -> Option<String> {
+        to_json(self).ok()
+    }
+}
+
+/// The parts of a browser `storage` event the app needs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StorageNotice {
+    pub key: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+/// A key another tab wrote, with what this tab last saw under it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RemoteChange {
+    pub key: String,
+    pub previous: Option<String>,
+    pub value: Option<String>,
+}
+
+/// Calls `on_notice` for every `storage` event, i.e. for every `localStorage`
+/// write made by another tab of the app, for as long as the page lives.
+pub fn listen_for_changes(on_notice: impl Fn(StorageNotice) + 'static) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let listener = Closure::<dyn Fn(StorageEvent)>::new(move |event: StorageEvent| {
+        // A missing key means another tab cleared all of localStorage.
+        if let Some(key) = event.key() {
+            on_notice(StorageNotice {
+                key,
+                old_value: event.old_value(),
+                new_value: event.new_value(),
+            });
+        }
+    });
+    if window
+        .add_event_listener_with_callback("storage", listener.as_ref().unchecked_ref())
+        .is_ok()
+    {
+        listener.forget();
+    }
+}
+
+impl<B: StorageBackend> HistoryManager<B> {
+    /// Folds another tab's write into this history. Returns whether the change
+    /// concerns this history at all, i.e. whether views of it should reload.
+    ///
+    /// Two tabs that both append to the same monthly bucket would otherwise lose
+    /// one of the sessions: the later write replaces the earlier. The bucket is
+    /// merged instead, as the union of both versions minus the sessions in the
+    /// trash or purged from it, and written back when that adds anything the
+    /// other tab missed.
+    pub fn apply_remote(&self, change: &RemoteChange) -> bool {
+        let Some(name) = change.key.strip_prefix(&self.namespace) else {
+            return false;
+        };
+        let Some(month) = name.strip_prefix(BUCKET_PREFIX) else {
+            return true;
+        };
+
+        let theirs = parse_bucket(change.value.as_deref());
+        let ours = parse_bucket(change.previous.as_deref());
+        let mut deleted = self.purged();
+        deleted.extend(
+            self.trash()
+                .iter()
+                .flat_map(|entry| entry.records())
+                .map(|record| record.identity()),
+        );
+        let merged = merge_buckets(&theirs, &ours, &deleted);
+
+        if merged != theirs {
+            let written = self
+                .write_bucket(month, &merged)
+                .and_then(|()| self.add_to_index([month.to_string()]));
+            if let Err(error) = written {
+                web_sys::console::warn_1(
+                    &format!("Could not merge sessions from another tab: {error}").into(),
+                );
+            }
+        } else if !merged.is_empty() {
+            let _ = self.add_to_index([month.to_string()]);
+        }
+        self.backend.remove(&self.key(AGGREGATE));
+        true
+    }
+}
+
+fn parse_bucket(data: Option<&str>) -> Vec<Value> {
+    data.and_then(|data| serde_json::from_str(data).ok())
+        .unwrap_or_default()
+}
+
+/// Every entry of either version once, leaving out deleted sessions, oldest first.
+fn merge_buckets(theirs: &[Value], ours: &[Value], deleted: &HashSet<Identity>) -> Vec<Value> {
+    let identity = |entry: &Value| {
+        migration::upgrade_value(entry.clone())
+            .ok()
+            .map(|upgraded| SessionRecord::identity(&upgraded.record))
+    };
+    let mut seen = HashSet::new();
+    let mut merged = Vec::new();
+    for entry in theirs.iter().chain(ours) {
+        let keep = match identity(entry) {
+            Some(identity) => !deleted.contains(&identity) && seen.insert(identity),
+            // Unreadable entries have no identity; keep one copy of each.
+            None => !merged.contains(entry),
+        };
+        if keep {
+            merged.push(entry.clone());
+        }
+    }
+    sort_by_time(&mut merged);
+    merged
+}
+
+/// Whether `key` is one a remote change should be looked at for.
+pub(super) fn is_synced_key(key: &str) -> bool {
+    key.starts_with(APP_KEY_PREFIX) && key != SYNC_KEY
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RemoteChange;
+    use crate::storage::backend::MemoryBackend;
+    use crate::storage::{migration, HistoryManager, SessionRecord, StorageBackend};
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn record(hour: u32, text: &str) -> SessionRecord {
+        SessionRecord {
+            wpm: 10.0,
+            recorded_at: Utc.with_ymd_and_hms(2026, 3, 1, hour, 0, 0).unwrap(),
+            exercise_text: text.to_string(),
+            ..SessionRecord::default()
+        }
+    }
+
+    /// The change as this tab receives it: the other tab's value, and this tab's.
+    fn change(backend: &MemoryBackend, key: &str, previous: Option<String>) -> RemoteChange {
+        RemoteChange {
+            key: key.to_string(),
+            previous,
+            value: backend.get(key),
+        }
+    }
+
+    #[test]
+    fn concurrent_appends_to_a_bucket_are_merged() {
+        let backend = MemoryBackend::default();
+        let history = HistoryManager::new(backend.clone());
+        let bucket = "_array30_bucket_2026-03";
+        history.save_session(record(9, "一")).unwrap();
+        history.save_session(record(10, "二")).unwrap();
+        let ours = backend.get(bucket);
+
+        // Another tab that had not seen 二 yet saves 三, replacing the bucket.
+        let theirs: Vec<_> = [record(9, "一"), record(11, "三")]
+            .iter()
+            .map(|record| migration::to_stored_value(record).unwrap())
+            .collect();
+        backend
+            .set(bucket, &serde_json::to_string(&theirs).unwrap())
+            .unwrap();
+
+        assert!(history.apply_remote(&change(&backend, bucket, ours)));
+
+        let texts: Vec<String> = history
+            .load_records()
+            .into_iter()
+            .map(|record| record.exercise_text)
+            .collect();
+        assert_eq!(texts, ["一", "二", "三"]);
+        assert_eq!(history.statistics().total_sessions, 3);
+        assert!(
+            !HistoryManager::for_profile(backend.clone(), "x").apply_remote(&change(
+                &backend,
+                "_array30_profile_y_progress",
+                None
+            ))
+        );
+    }
+
+    #[test]
+    fn merging_does_not_bring_back_deleted_sessions() {
+        let backend = MemoryBackend::default();
+        let history = HistoryManager::new(backend.clone());
+        let bucket = "_array30_bucket_2026-03";
+        history.save_session(record(9, "一")).unwrap();
+        history.save_session(record(10, "二")).unwrap();
+        let before = backend.get(bucket);
+
+        history
+            .delete_session(&record(9, "一"), Utc::now())
+            .unwrap();
+        let after = backend.get(bucket);
+
+        // A tab that missed the deletion writes its stale copy back.
+        backend.set(bucket, &before.unwrap()).unwrap();
+        history.apply_remote(&change(&backend, bucket, after.clone()));
+
+        assert_eq!(backend.get(bucket), after);
+        assert_eq!(history.load_records(), [record(10, "二")]);
+    }
+
+    #[test]
+    fn merging_does_not_bring_back_purged_sessions() {
+        let backend = MemoryBackend::default();
+        let history = HistoryManager::new(backend.clone());
+        let bucket = "_array30_bucket_2026-03";
+        let deleted_at = Utc.with_ymd_and_hms(2026, 3, 2, 12, 0, 0).unwrap();
+        history.save_session(record(9, "一")).unwrap();
+        history.save_session(record(10, "二")).unwrap();
+        let before = backend.get(bucket);
+
+        history
+            .delete_session(&record(9, "一"), deleted_at)
+            .unwrap();
+        assert_eq!(history.purge_trash(deleted_at + Duration::days(30)), 1);
+        assert!(history.trash().is_empty());
+        let after = backend.get(bucket);
+
+        // A tab left open since before the deletion writes its stale copy back.
+        backend.set(bucket, &before.unwrap()).unwrap();
+        history.apply_remote(&change(&backend, bucket, after.clone()));
+
+        assert_eq!(backend.get(bucket), after);
+        assert_eq!(history.load_records(), [record(10, "二")]);
+
+        // Importing the session again is deliberate, so it merges like any other.
+        assert_eq!(
+            history.import_records(vec![record(9, "一")]).unwrap().added,
+            1
+        );
+        let imported = backend.get(bucket);
+        history.apply_remote(&change(&backend, bucket, after));
+        assert_eq!(backend.get(bucket), imported);
+        assert_eq!(history.load_records(), [record(9, "一"), record(10, "二")]);
+    }
+}
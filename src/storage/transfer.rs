@@ -0,0 +1,174 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use super::{migration, SessionRecord, SCHEMA_VERSION};
+
+/// Marks a JSON file as an export from this app.
+const EXPORT_FORMAT: &str = "array30-typing-tutor-history";
+
+#[derive(Serialize)]
+struct ExportFile {
+    format: &'static str,
+    schema_version: u64,
+    exported_at: String,
+    sessions: Vec<Value>,
+}
+
+/// Full history as JSON, with every field of every record; [`parse_import`] reads it back.
+pub fn export_json(records: &[SessionRecord]) -> serde_json::Result<String> {
+    let sessions = records
+        .iter()
+        .map(migration::to_stored_value)
+        .collect::<serde_json::Result<_>>()?;
+    serde_json::to_string_pretty(&ExportFile {
+        format: EXPORT_FORMAT,
+        schema_version: SCHEMA_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        sessions,
+    })
+}
+
+const CSV_HEADER: [&str; 13] = [
+    "recorded_at",
+    "mode",
+    "exercise_text",
+    "wpm",
+    "net_wpm",
+    "gross_wpm",
+    "accuracy",
+    "elapsed_ms",
+    "wall_ms",
+    "corrected_errors",
+    "uncorrected_errors",
+    "deletions",
+    "composing_ms",
+];
+
+/// One row per session with the summary figures, for spreadsheets. Times are
+/// RFC 3339 on the clock they were recorded on. Event logs and per-character
+/// timings only go into the JSON export.
+pub fn export_csv(records: &[SessionRecord]) -> String {
+    let mut csv = CSV_HEADER.join(",");
+    csv.push_str("\r\n");
+    for record in records {
+        let row = [
+            record
+                .recorded_at
+                .with_timezone(&record.recorded_offset())
+                .to_rfc3339(),
+            record.mode.to_string(),
+            record.exercise_text.clone(),
+            format!("{:.2}", record.wpm),
+            format!("{:.2}", record.net_wpm),
+            format!("{:.2}", record.gross_wpm),
+            format!("{:.2}", record.accuracy),
+            record.elapsed_ms.to_string(),
+            record.wall_ms.to_string(),
+            record.corrected_errors.to_string(),
+            record.uncorrected_errors.to_string(),
+            record.deletions.to_string(),
+            record.composing_ms.to_string(),
+        ];
+        let row: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&row.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Records read from an import file, plus the entries that could not be read.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImportBatch {
+    pub records: Vec<SessionRecord>,
+    /// Position in the file and the reason, for each unreadable entry.
+    pub failed: Vec<(usize, String)>,
+}
+
+/// Reads a JSON export. A bare array of records is accepted too, and records of
+/// older schema versions are upgraded the same way stored ones are.
+pub fn parse_import(data: &str) -> Result<ImportBatch, String> {
+    let value: Value =
+        serde_json::from_str(data).map_err(|error| format!("not a JSON file: {error}"))?;
+    let sessions = match value {
+        Value::Array(sessions) => sessions,
+        Value::Object(mut file) => match file.remove("sessions") {
+            Some(Value::Array(sessions)) => sessions,
+            _ => return Err("no \"sessions\" list in the file".to_string()),
+        },
+        _ => return Err("not a history export".to_string()),
+    };
+
+    let mut batch = ImportBatch::default();
+    for (index, session) in sessions.into_iter().enumerate() {
+        match migration::upgrade_value(session) {
+            Ok(upgraded) => batch.records.push(upgraded.record),
+            Err(reason) => batch.failed.push((index, reason)),
+        }
+    }
+    Ok(batch)
+}
+
+/// Outcome of [`super::HistoryManager::import_records`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ImportSummary {
+    pub added: usize,
+    /// Records already in the history (same recording time and content), skipped.
+    pub duplicates: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{export_csv, export_json, parse_import};
+    use crate::storage::SessionRecord;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn json_export_round_trips_and_csv_quotes_fields() {
+        let records = vec![
+            SessionRecord {
+                wpm: 12.5,
+                recorded_at: Utc.with_ymd_and_hms(2026, 1, 1, 2, 0, 0).unwrap(),
+                utc_offset_minutes: 480,
+                elapsed_ms: 4_000,
+                exercise_text: "你好, \"世界\"".to_string(),
+                ..SessionRecord::default()
+            },
+            SessionRecord::default(),
+        ];
+
+        let batch = parse_import(&export_json(&records).unwrap()).unwrap();
+        assert_eq!(batch.records, records);
+        assert!(batch.failed.is_empty());
+
+        let csv = export_csv(&records);
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 3);
+        assert!(
+            rows[1].starts_with("2026-01-01T10:00:00+08:00,Lesson,\"你好, \"\"世界\"\"\",12.50,")
+        );
+    }
+
+    #[test]
+    fn parse_import_upgrades_old_records_and_reports_bad_ones() {
+        let data = r#"[
+            {"wpm":1.0,"accuracy":90.0,"timestamp":"2026-01-01 09:00:00","elapsed_seconds":2,"exercise_text":"一"},
+            {"wpm":"fast"}
+        ]"#;
+
+        let batch = parse_import(data).unwrap();
+
+        assert_eq!(batch.records.len(), 1);
+        assert_eq!(batch.records[0].elapsed_ms, 2_000);
+        assert_eq!(batch.failed.len(), 1);
+        assert_eq!(batch.failed[0].0, 1);
+        assert!(parse_import("{}").is_err());
+    }
+}
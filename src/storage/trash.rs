@@ -0,0 +1,253 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{
+    migration, stored_value, to_json, HistoryManager, Identity, ImportSummary, SessionRecord,
+    StorageBackend, StorageError, AGGREGATE, PURGED, TRASH,
+};
+
+/// How long deleted sessions can be restored before they are purged.
+pub const TRASH_RETENTION_DAYS: i64 = 7;
+
+/// Sessions removed together, by deleting one of them or by a reset.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct TrashEntry {
+    /// Also identifies the entry when restoring it.
+    pub deleted_at: DateTime<Utc>,
+    /// Whether the whole history was reset rather than a single session deleted.
+    pub reset: bool,
+    /// Kept in stored form, so restored records are upgraded like any other.
+    sessions: Vec<Value>,
+}
+
+impl TrashEntry {
+    pub fn session_count(&self) -> usize {
+        self.sessions.len()
+    }
+
+    pub fn records(&self) -> Vec<SessionRecord> {
+        self.sessions
+            .iter()
+            .filter_map(|entry| migration::upgrade_value(entry.clone()).ok())
+            .map(|upgraded| upgraded.record)
+            .collect()
+    }
+
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.deleted_at + Duration::days(TRASH_RETENTION_DAYS)
+    }
+}
+
+impl<B: StorageBackend> HistoryManager<B> {
+    /// Deleted sessions that can still be restored, oldest deletion first.
+    pub fn trash(&self) -> Vec<TrashEntry> {
+        self.load_value(TRASH).unwrap_or_default()
+    }
+
+    /// Moves one session to the trash; `false` when it is not in the history.
+    pub fn delete_session(
+        &self,
+        record: &SessionRecord,
+        now: DateTime<Utc>,
+    ) -> Result<bool, StorageError> {
+        let identity = record.identity();
+        for month in self.bucket_index() {
+            let mut entries = self.read_bucket(&month)?;
+            let Some(index) = entries.iter().position(|entry| {
+                migration::upgrade_value(entry.clone())
+                    .is_ok_and(|upgraded| upgraded.record.identity() == identity)
+            }) else {
+                continue;
+            };
+            let removed = entries.remove(index);
+            // Trash first: if the bucket write fails the session is in both, and
+            // restoring it skips the copy still in the history.
+            self.add_to_trash(TrashEntry {
+                deleted_at: now,
+                reset: false,
+                sessions: vec![removed],
+            })?;
+            self.write_bucket(&month, &entries)?;
+            self.backend.remove(&self.key(AGGREGATE));
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// Moves every readable session to the trash and clears the rest of the history.
+    pub fn reset_history(&self, now: DateTime<Utc>) -> Result<(), StorageError> {
+        let sessions = self
+            .load_records()
+            .iter()
+            .map(stored_value)
+            .collect::<Result<Vec<_>, _>>()?;
+        if !sessions.is_empty() {
+            self.add_to_trash(TrashEntry {
+                deleted_at: now,
+                reset: true,
+                sessions,
+            })?;
+        }
+        let kept = [self.key(TRASH), self.key(PURGED)];
+        for key in self.stored_keys() {
+            if !kept.contains(&key) {
+                self.backend.remove(&key);
+            }
+        }
+        Ok(())
+    }
+
+    /// Puts the sessions of the entry deleted at `deleted_at` back into the history.
+    pub fn restore_trash(&self, deleted_at: DateTime<Utc>) -> Result<ImportSummary, StorageError> {
+        let mut trash = self.trash();
+        let Some(index) = trash
+            .iter()
+            .position(|entry| entry.deleted_at == deleted_at)
+        else {
+            return Ok(ImportSummary::default());
+        };
+        // Out of the trash first, so that other tabs merging the restored
+        // sessions do not take them for deleted ones.
+        let entry = trash.remove(index);
+        self.save_trash(&trash)?;
+        match self.import_records(entry.records()) {
+            Ok(summary) => Ok(summary),
+            Err(error) => {
+                let _ = self.add_to_trash(entry);
+                Err(error)
+            }
+        }
+    }
+
+    /// Drops trash entries older than [`TRASH_RETENTION_DAYS`]; returns how many.
+    pub fn purge_trash(&self, now: DateTime<Utc>) -> usize {
+        let (expired, trash): (Vec<_>, Vec<_>) = self
+            .trash()
+            .into_iter()
+            .partition(|entry| entry.expires_at() <= now);
+        if expired.is_empty() {
+            return 0;
+        }
+        // Remembered before leaving the trash, so that merges always find them
+        // in one or the other.
+        let mut purged = self.purged();
+        purged.extend(
+            expired
+                .iter()
+                .flat_map(TrashEntry::records)
+                .map(|record| record.identity()),
+        );
+        if self.save_purged(&purged).is_ok() {
+            let _ = self.save_trash(&trash);
+        }
+        expired.len()
+    }
+
+    /// Sessions purged from the trash. A tab that has not seen the deletion can
+    /// still write them back, so merges leave these out like those in the trash.
+    pub(super) fn purged(&self) -> HashSet<Identity> {
+        self.load_value::<Vec<Identity>>(PURGED)
+            .unwrap_or_default()
+            .into_iter()
+            .collect()
+    }
+
+    /// Stops leaving `records` out of merges, as they were brought back on purpose.
+    pub(super) fn forget_purged(&self, records: &[SessionRecord]) -> Result<(), StorageError> {
+        let mut purged = self.purged();
+        let before = purged.len();
+        for record in records {
+            purged.remove(&record.identity());
+        }
+        if purged.len() == before {
+            return Ok(());
+        }
+        self.save_purged(&purged)
+    }
+
+    fn save_purged(&self, purged: &HashSet<Identity>) -> Result<(), StorageError> {
+        if purged.is_empty() {
+            self.backend.remove(&self.key(PURGED));
+            return Ok(());
+        }
+        let mut purged: Vec<_> = purged.iter().collect();
+        purged.sort();
+        self.save_value(PURGED, &purged)
+    }
+
+    fn add_to_trash(&self, entry: TrashEntry) -> Result<(), StorageError> {
+        let mut trash = self.trash();
+        trash.push(entry);
+        self.save_trash(&trash)
+    }
+
+    fn save_trash(&self, trash: &[TrashEntry]) -> Result<(), StorageError> {
+        if trash.is_empty() {
+            self.backend.remove(&self.key(TRASH));
+            return Ok(());
+        }
+        self.backend.set(&self.key(TRASH), &to_json(trash)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::backend::MemoryBackend;
+    use crate::storage::{HistoryManager, SessionRecord, StorageBackend};
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn record(day: u32, text: &str) -> SessionRecord {
+        SessionRecord {
+            wpm: 10.0,
+            recorded_at: Utc.with_ymd_and_hms(2026, 3, day, 9, 0, 0).unwrap(),
+            exercise_text: text.to_string(),
+            ..SessionRecord::default()
+        }
+    }
+
+    #[test]
+    fn deleted_sessions_go_to_the_trash_and_come_back() {
+        let history = HistoryManager::new(MemoryBackend::default());
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        history.save_session(record(1, "一")).unwrap();
+        history.save_session(record(2, "二")).unwrap();
+
+        assert!(history.delete_session(&record(1, "一"), now).unwrap());
+        assert!(!history.delete_session(&record(1, "一"), now).unwrap());
+
+        assert_eq!(history.load_records(), [record(2, "二")]);
+        assert_eq!(history.statistics().total_sessions, 1);
+        let trash = history.trash();
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].records(), [record(1, "一")]);
+
+        assert_eq!(history.restore_trash(now).unwrap().added, 1);
+        assert_eq!(history.load_records(), [record(1, "一"), record(2, "二")]);
+        assert!(history.trash().is_empty());
+    }
+
+    #[test]
+    fn reset_can_be_undone_until_the_trash_is_purged() {
+        let backend = MemoryBackend::default();
+        let history = HistoryManager::new(backend.clone());
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        history.save_session(record(1, "一")).unwrap();
+        history.save_session(record(2, "二")).unwrap();
+
+        history.reset_history(now).unwrap();
+
+        assert_eq!(backend.keys(), ["_array30_trash"]);
+        assert!(history.load_records().is_empty());
+        assert_eq!(history.statistics().total_sessions, 0);
+        let entry = &history.trash()[0];
+        assert!(entry.reset);
+        assert_eq!(entry.session_count(), 2);
+
+        assert_eq!(history.purge_trash(now + Duration::days(6)), 0);
+        assert_eq!(history.purge_trash(now + Duration::days(7)), 1);
+        assert!(backend.get("_array30_trash").is_none());
+    }
+}
@@ -0,0 +1,140 @@
+use crate::storage::SessionRecord;
+use chrono::{DateTime, Datelike, Days, FixedOffset, Local, NaiveDate, Offset, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+/// The clock that history dates are shown in and grouped into days and weeks by.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeZoneChoice {
+    /// The device's time zone, with its daylight saving rules.
+    #[default]
+    Device,
+    /// Each session on the clock of the place it was recorded.
+    AsRecorded,
+    /// A fixed offset from UTC, in minutes.
+    Fixed(i32),
+}
+
+/// Offsets offered for [`TimeZoneChoice::Fixed`], in minutes east of UTC.
+pub const FIXED_OFFSETS: [i32; 30] = [
+    -720, -660, -600, -540, -480, -420, -360, -300, -240, -180, -120, -60, 0, 60, 120, 180, 210,
+    240, 270, 300, 330, 345, 360, 420, 480, 540, 570, 600, 660, 720,
+];
+
+impl TimeZoneChoice {
+    /// When the session was recorded, on this clock.
+    pub fn local_time(self, record: &SessionRecord) -> DateTime<FixedOffset> {
+        let offset = match self {
+            TimeZoneChoice::Device => Local
+                .offset_from_utc_datetime(&record.recorded_at.naive_utc())
+                .fix(),
+            TimeZoneChoice::AsRecorded => record.recorded_offset(),
+            TimeZoneChoice::Fixed(minutes) => fixed_offset(minutes),
+        };
+        record.recorded_at.with_timezone(&offset)
+    }
+
+    /// The calendar day the session belongs to.
+    pub fn day(self, record: &SessionRecord) -> NaiveDate {
+        self.local_time(record).date_naive()
+    }
+
+    /// Today's date on this clock. Sessions shown as recorded count against the
+    /// device's today, being the only "now" there is.
+    pub fn today(self) -> NaiveDate {
+        let now = Utc::now();
+        match self {
+            TimeZoneChoice::Device | TimeZoneChoice::AsRecorded => {
+                now.with_timezone(&Local).date_naive()
+            }
+            TimeZoneChoice::Fixed(minutes) => {
+                now.with_timezone(&fixed_offset(minutes)).date_naive()
+            }
+        }
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            TimeZoneChoice::Device => "This device's time zone".to_string(),
+            TimeZoneChoice::AsRecorded => "Where each session was recorded".to_string(),
+            TimeZoneChoice::Fixed(minutes) => format_offset(minutes),
+        }
+    }
+
+    /// A stable value for `<option>` elements, read back by [`TimeZoneChoice::parse`].
+    pub fn value(self) -> String {
+        match self {
+            TimeZoneChoice::Device => "device".to_string(),
+            TimeZoneChoice::AsRecorded => "recorded".to_string(),
+            TimeZoneChoice::Fixed(minutes) => minutes.to_string(),
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "device" => Some(TimeZoneChoice::Device),
+            "recorded" => Some(TimeZoneChoice::AsRecorded),
+            minutes => minutes.parse().ok().map(TimeZoneChoice::Fixed),
+        }
+    }
+}
+
+/// The Monday on or before `day`.
+pub fn week_start(day: NaiveDate) -> NaiveDate {
+    day - Days::new(day.weekday().num_days_from_monday().into())
+}
+
+/// The device's current offset from UTC, in minutes, for new records.
+pub fn device_offset_minutes() -> i32 {
+    Local::now().offset().local_minus_utc() / 60
+}
+
+/// Offsets outside ±24h cannot come from a real clock; they fall back to UTC.
+pub fn fixed_offset(minutes: i32) -> FixedOffset {
+    FixedOffset::east_opt(minutes * 60).unwrap_or(Utc.fix())
+}
+
+/// `UTC+08:00`, `UTC-03:30`.
+pub fn format_offset(minutes: i32) -> String {
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let minutes = minutes.unsigned_abs();
+    format!("UTC{sign}{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_offset, week_start, TimeZoneChoice};
+    use crate::storage::SessionRecord;
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    #[test]
+    fn days_and_weeks_follow_the_chosen_clock() {
+        // Monday 2026-03-02 01:30 in Taipei is still Sunday in UTC.
+        let record = SessionRecord {
+            recorded_at: Utc.with_ymd_and_hms(2026, 3, 1, 17, 30, 0).unwrap(),
+            utc_offset_minutes: 480,
+            ..SessionRecord::default()
+        };
+        let date = |day| NaiveDate::from_ymd_opt(2026, 3, day).unwrap();
+
+        assert_eq!(TimeZoneChoice::AsRecorded.day(&record), date(2));
+        assert_eq!(week_start(TimeZoneChoice::AsRecorded.day(&record)), date(2));
+        assert_eq!(TimeZoneChoice::Fixed(0).day(&record), date(1));
+        assert_eq!(
+            week_start(TimeZoneChoice::Fixed(0).day(&record)),
+            NaiveDate::from_ymd_opt(2026, 2, 23).unwrap()
+        );
+        assert_eq!(
+            TimeZoneChoice::Fixed(-300).local_time(&record).to_rfc3339(),
+            "2026-03-01T12:30:00-05:00"
+        );
+        assert_eq!(format_offset(-210), "UTC-03:30");
+        for choice in [
+            TimeZoneChoice::Device,
+            TimeZoneChoice::AsRecorded,
+            TimeZoneChoice::Fixed(345),
+        ] {
+            assert_eq!(TimeZoneChoice::parse(&choice.value()), Some(choice));
+        }
+    }
+}
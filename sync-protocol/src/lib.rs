@@ -0,0 +1,288 @@
+//! What the typing tutor and its sync server exchange, and the bookkeeping both
+//! ends need to stay consistent.
+//!
+//! A synced history lives on the server as an append-only log of [`Change`]s in
+//! a *space*, a name the learner enters on every device that should share one
+//! history. Devices push the changes they made and pull whatever was appended
+//! after the last [cursor](PullResponse::cursor) they saw.
+//!
+//! Session records never change once saved, so the only thing two devices can
+//! disagree on is whether a session is kept or deleted. The later change in the
+//! log wins.
+//!
+//! - `POST /v1/spaces/{space}/changes` takes a [`PushRequest`] and answers a
+//!   [`PushResponse`].
+//! - `GET /v1/spaces/{space}/changes?since={cursor}` answers a [`PullResponse`].
+//!
+//! Both take an optional `Authorization: Bearer <token>` header.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Most changes a single pull returns, and a device pushes at once.
+/// [`PullResponse::more`] says when to pull again.
+pub const BATCH_SIZE: usize = 200;
+
+/// Longest space name the server accepts.
+pub const MAX_SPACE_LEN: usize = 64;
+
+/// Path of the changes of `space`, relative to the server's base URL.
+pub fn changes_path(space: &str) -> String {
+    format!("/v1/spaces/{space}/changes")
+}
+
+/// Space names are ASCII letters, digits, `-` and `_`, so they can be used in
+/// URLs and file names as they are.
+pub fn is_valid_space(space: &str) -> bool {
+    !space.is_empty()
+        && space.len() <= MAX_SPACE_LEN
+        && space
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_')
+}
+
+/// One session added to or deleted from the history.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Change {
+    /// Identifies the session on every device; derived from the record itself.
+    pub id: String,
+    /// The session in the app's stored form, or `None` when it was deleted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub record: Option<Value>,
+}
+
+impl Change {
+    pub fn is_deletion(&self) -> bool {
+        self.record.is_none()
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct PushRequest {
+    pub changes: Vec<Change>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct PushResponse {
+    /// How many of the pushed changes were news to the server.
+    pub appended: usize,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct PullResponse {
+    /// Oldest first.
+    pub changes: Vec<Change>,
+    /// Where the next pull continues from.
+    pub cursor: u64,
+    /// Whether more changes follow the cursor than fitted in this response.
+    #[serde(default)]
+    pub more: bool,
+}
+
+/// A space's log. Changes are numbered from 1 in the order they were appended;
+/// a cursor is the number of changes already seen.
+#[derive(Clone, Debug, Default)]
+pub struct Log {
+    changes: Vec<Change>,
+    /// Whether each session is currently kept, going by its latest change.
+    kept: HashMap<String, bool>,
+}
+
+impl Log {
+    pub fn from_changes(changes: impl IntoIterator<Item = Change>) -> Self {
+        let mut log = Log::default();
+        for change in changes {
+            log.kept.insert(change.id.clone(), !change.is_deletion());
+            log.changes.push(change);
+        }
+        log
+    }
+
+    pub fn cursor(&self) -> u64 {
+        self.changes.len() as u64
+    }
+
+    /// Appends the changes that alter the space and returns them, leaving out
+    /// sessions already there and deletions of ones that are not.
+    pub fn append(&mut self, changes: Vec<Change>) -> Vec<Change> {
+        let mut appended = Vec::new();
+        for change in changes {
+            let keep = !change.is_deletion();
+            let kept = self.kept.get(&change.id).copied().unwrap_or(false);
+            if keep == kept {
+                continue;
+            }
+            self.kept.insert(change.id.clone(), keep);
+            self.changes.push(change.clone());
+            appended.push(change);
+        }
+        appended
+    }
+
+    /// Up to [`BATCH_SIZE`] changes appended after `cursor`.
+    pub fn since(&self, cursor: u64) -> PullResponse {
+        let start = (cursor as usize).min(self.changes.len());
+        let end = (start + BATCH_SIZE).min(self.changes.len());
+        PullResponse {
+            changes: self.changes[start..end].to_vec(),
+            cursor: end as u64,
+            more: end < self.changes.len(),
+        }
+    }
+}
+
+/// What a device remembers between syncs.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct SyncState {
+    /// Changes of the space already applied.
+    pub cursor: u64,
+    /// Sessions the server has as kept. One of these missing from the device was
+    /// deleted there; any other session on the device is new to the server.
+    pub known: BTreeSet<String>,
+}
+
+/// What a pull asks the device to change in its history.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Incoming {
+    /// Records to add, by id.
+    pub added: BTreeMap<String, Value>,
+    /// Ids of sessions on the device to delete.
+    pub deleted: BTreeSet<String>,
+}
+
+impl SyncState {
+    /// Takes in a pull, given the ids of the sessions on the device, and returns
+    /// what has to change there. A session deleted on the device but not pushed
+    /// yet is not added back; the deletion is pushed next instead.
+    pub fn apply(&mut self, pull: PullResponse, local: &BTreeSet<String>) -> Incoming {
+        let mut incoming = Incoming::default();
+        for change in pull.changes {
+            match change.record {
+                Some(record) => {
+                    if self.known.insert(change.id.clone()) && !local.contains(&change.id) {
+                        incoming.added.insert(change.id, record);
+                    }
+                }
+                None => {
+                    self.known.remove(&change.id);
+                    if incoming.added.remove(&change.id).is_none() && local.contains(&change.id) {
+                        incoming.deleted.insert(change.id);
+                    }
+                }
+            }
+        }
+        self.cursor = pull.cursor;
+        incoming
+    }
+
+    /// The changes the server has not seen, given the device's sessions by id.
+    pub fn pending(&self, local: &BTreeMap<String, Value>) -> Vec<Change> {
+        let added = local
+            .iter()
+            .filter(|(id, _)| !self.known.contains(*id))
+            .map(|(id, record)| Change {
+                id: id.clone(),
+                record: Some(record.clone()),
+            });
+        let deleted = self
+            .known
+            .iter()
+            .filter(|id| !local.contains_key(*id))
+            .map(|id| Change {
+                id: id.clone(),
+                record: None,
+            });
+        added.chain(deleted).collect()
+    }
+
+    /// Records that the server accepted `changes`.
+    pub fn pushed(&mut self, changes: &[Change]) {
+        for change in changes {
+            if change.is_deletion() {
+                self.known.remove(&change.id);
+            } else {
+                self.known.insert(change.id.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn kept(id: &str) -> Change {
+        Change {
+            id: id.to_string(),
+            record: Some(json!({ "text": id })),
+        }
+    }
+
+    fn deleted(id: &str) -> Change {
+        Change {
+            id: id.to_string(),
+            record: None,
+        }
+    }
+
+    #[test]
+    fn log_keeps_only_changes_that_alter_the_space() {
+        let mut log = Log::default();
+        assert_eq!(log.append(vec![kept("a"), kept("b"), kept("a")]).len(), 2);
+        assert_eq!(log.append(vec![deleted("a"), deleted("c")]), [deleted("a")]);
+        assert_eq!(log.append(vec![kept("a")]), [kept("a")]);
+
+        let pull = log.since(1);
+        assert_eq!(pull.changes, [kept("b"), deleted("a"), kept("a")]);
+        assert_eq!(pull.cursor, 4);
+        assert!(!pull.more);
+        assert_eq!(Log::from_changes(log.since(0).changes).cursor(), 4);
+        assert!(log.since(10).changes.is_empty());
+    }
+
+    #[test]
+    fn devices_converge_through_the_log() {
+        let mut log = Log::default();
+        let mut phone = SyncState::default();
+        let mut laptop = SyncState::default();
+        let mut phone_sessions: BTreeMap<_, _> = [kept("a"), kept("b")]
+            .into_iter()
+            .map(|change| (change.id, change.record.unwrap()))
+            .collect();
+
+        let pending = phone.pending(&phone_sessions);
+        log.append(pending.clone());
+        phone.pushed(&pending);
+
+        let incoming = laptop.apply(log.since(laptop.cursor), &BTreeSet::new());
+        assert_eq!(incoming.added.keys().collect::<Vec<_>>(), ["a", "b"]);
+        let laptop_sessions = incoming.added;
+        assert!(laptop.pending(&laptop_sessions).is_empty());
+
+        // The phone deletes "a" before hearing its own changes back.
+        phone_sessions.remove("a");
+        let local = phone_sessions.keys().cloned().collect();
+        let incoming = phone.apply(log.since(phone.cursor), &local);
+        assert_eq!(incoming, Incoming::default());
+        let pending = phone.pending(&phone_sessions);
+        assert_eq!(pending, [deleted("a")]);
+        log.append(pending);
+
+        let local = laptop_sessions.keys().cloned().collect();
+        let incoming = laptop.apply(log.since(laptop.cursor), &local);
+        assert_eq!(incoming.deleted.iter().collect::<Vec<_>>(), ["a"]);
+        assert!(incoming.added.is_empty());
+    }
+
+    #[test]
+    fn space_names_are_url_and_file_safe() {
+        assert!(is_valid_space("sirius-home_2"));
+        assert!(!is_valid_space(""));
+        assert!(!is_valid_space("../etc"));
+        assert!(!is_valid_space("a b"));
+        assert!(!is_valid_space(&"x".repeat(MAX_SPACE_LEN + 1)));
+    }
+}
@@ -0,0 +1,207 @@
+//! Reference server for syncing typing tutor histories between devices; see
+//! [`array30_sync_protocol`] for the protocol.
+//!
+//! Each space is kept in `<data dir>/<space>.jsonl`, one [`Change`] per line in
+//! the order they were appended. Files are only ever appended to, so backing one
+//! up is a matter of copying it.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path as FsPath, PathBuf};
+use std::sync::Arc;
+
+use array30_sync_protocol::{
+    changes_path, is_valid_space, Change, Log, PullResponse, PushRequest, PushResponse,
+};
+use axum::extract::{DefaultBodyLimit, Path, Query, State};
+use axum::http::{header, HeaderMap, Method, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Deserialize;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+use tokio::sync::Mutex;
+use tower_http::cors::{Any, CorsLayer};
+
+/// Large enough for a first push of [`array30_sync_protocol::BATCH_SIZE`]
+/// sessions with their keystroke logs.
+const BODY_LIMIT: usize = 64 * 1024 * 1024;
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Directory holding one file per space; created when missing.
+    pub data_dir: PathBuf,
+    /// When set, requests must carry it as a bearer token.
+    pub token: Option<String>,
+}
+
+struct Server {
+    config: Config,
+    /// Spaces read so far. One lock for all of them keeps appends to a file in order.
+    spaces: Mutex<HashMap<String, Log>>,
+}
+
+/// The sync API, answering cross-origin requests as the app is usually served
+/// from somewhere else.
+pub fn router(config: Config) -> Router {
+    let server = Arc::new(Server {
+        config,
+        spaces: Mutex::new(HashMap::new()),
+    });
+    let cors = CorsLayer::new()
+        .allow_origin(Any)
+        .allow_methods([Method::GET, Method::POST])
+        .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE]);
+    Router::new()
+        .route(&changes_path("{space}"), get(pull).post(push))
+        .layer(DefaultBodyLimit::max(BODY_LIMIT))
+        .layer(cors)
+        .with_state(server)
+}
+
+/// Serves [`router`] on `listener` until the process ends.
+pub async fn serve(listener: TcpListener, config: Config) -> io::Result<()> {
+    tokio::fs::create_dir_all(&config.data_dir).await?;
+    axum::serve(listener, router(config)).await
+}
+
+#[derive(Debug)]
+enum ServerError {
+    Unauthorized,
+    BadSpace,
+    Storage(io::Error),
+}
+
+impl IntoResponse for ServerError {
+    fn into_response(self) -> Response {
+        match self {
+            ServerError::Unauthorized => {
+                (StatusCode::UNAUTHORIZED, "missing or wrong token").into_response()
+            }
+            ServerError::BadSpace => (
+                StatusCode::BAD_REQUEST,
+                "space names are letters, digits, - and _",
+            )
+                .into_response(),
+            ServerError::Storage(error) => {
+                eprintln!("storage error: {error}");
+                (StatusCode::INTERNAL_SERVER_ERROR, "could not store changes").into_response()
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct PullQuery {
+    #[serde(default)]
+    since: u64,
+}
+
+async fn pull(
+    State(server): State<Arc<Server>>,
+    Path(space): Path<String>,
+    Query(query): Query<PullQuery>,
+    headers: HeaderMap,
+) -> Result<Json<PullResponse>, ServerError> {
+    server.check(&space, &headers)?;
+    let mut spaces = server.spaces.lock().await;
+    let log = server.log(&mut spaces, &space).await?;
+    Ok(Json(log.since(query.since)))
+}
+
+async fn push(
+    State(server): State<Arc<Server>>,
+    Path(space): Path<String>,
+    headers: HeaderMap,
+    Json(request): Json<PushRequest>,
+) -> Result<Json<PushResponse>, ServerError> {
+    server.check(&space, &headers)?;
+    let mut spaces = server.spaces.lock().await;
+    let log = server.log(&mut spaces, &space).await?;
+    let appended = log.append(request.changes);
+    if let Err(error) = append_to_file(&server.space_file(&space), &appended).await {
+        // Read the space again next time rather than serve what was not saved.
+        spaces.remove(&space);
+        return Err(ServerError::Storage(error));
+    }
+    Ok(Json(PushResponse {
+        appended: appended.len(),
+    }))
+}
+
+impl Server {
+    fn check(&self, space: &str, headers: &HeaderMap) -> Result<(), ServerError> {
+        if let Some(token) = &self.config.token {
+            let bearer = headers
+                .get(header::AUTHORIZATION)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.strip_prefix("Bearer "));
+            if bearer != Some(token.as_str()) {
+                return Err(ServerError::Unauthorized);
+            }
+        }
+        if !is_valid_space(space) {
+            return Err(ServerError::BadSpace);
+        }
+        Ok(())
+    }
+
+    fn space_file(&self, space: &str) -> PathBuf {
+        self.config.data_dir.join(format!("{space}.jsonl"))
+    }
+
+    async fn log<'a>(
+        &self,
+        spaces: &'a mut HashMap<String, Log>,
+        space: &str,
+    ) -> Result<&'a mut Log, ServerError> {
+        if !spaces.contains_key(space) {
+            let log = read_log(&self.space_file(space))
+                .await
+                .map_err(ServerError::Storage)?;
+            spaces.insert(space.to_string(), log);
+        }
+        Ok(spaces.get_mut(space).expect("space was just read"))
+    }
+}
+
+/// A missing file is an empty space. A line that cannot be parsed, such as one cut
+/// short by a crash, is skipped.
+async fn read_log(path: &FsPath) -> io::Result<Log> {
+    let data = match tokio::fs::read_to_string(path).await {
+        Ok(data) => data,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Log::default()),
+        Err(error) => return Err(error),
+    };
+    let changes = data.lines().enumerate().filter_map(|(index, line)| {
+        serde_json::from_str::<Change>(line)
+            .inspect_err(|error| {
+                eprintln!(
+                    "{}:{}: skipping unreadable change: {error}",
+                    path.display(),
+                    index + 1
+                )
+            })
+            .ok()
+    });
+    Ok(Log::from_changes(changes))
+}
+
+async fn append_to_file(path: &FsPath, changes: &[Change]) -> io::Result<()> {
+    if changes.is_empty() {
+        return Ok(());
+    }
+    let mut lines = String::new();
+    for change in changes {
+        lines.push_str(&serde_json::to_string(change)?);
+        lines.push('\n');
+    }
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await?;
+    file.write_all(lines.as_bytes()).await?;
+    file.sync_data().await
+}
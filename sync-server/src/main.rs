@@ -0,0 +1,63 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use array30_sync_server::{serve, Config};
+use tokio::net::TcpListener;
+
+const USAGE: &str = "\
+usage: array30-sync-server [--listen ADDRESS] [--data DIRECTORY]
+
+  --listen ADDRESS    where to accept connections (default 127.0.0.1:8787)
+  --data DIRECTORY    where to keep the synced histories (default ./sync-data)
+
+Set ARRAY30_SYNC_TOKEN to require that token from every client.";
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let mut listen: SocketAddr = ([127, 0, 0, 1], 8787).into();
+    let mut data_dir = PathBuf::from("sync-data");
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let parsed = match arg.as_str() {
+            "--listen" => args
+                .next()
+                .and_then(|value| value.parse().ok())
+                .map(|address| listen = address),
+            "--data" => args.next().map(|value| data_dir = value.into()),
+            _ => None,
+        };
+        if parsed.is_none() {
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    }
+    let token = std::env::var("ARRAY30_SYNC_TOKEN")
+        .ok()
+        .filter(|token| !token.is_empty());
+
+    let listener = match TcpListener::bind(listen).await {
+        Ok(listener) => listener,
+        Err(error) => {
+            eprintln!("cannot listen on {listen}: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+    println!(
+        "Syncing histories in {} on http://{listen}{}",
+        data_dir.display(),
+        if token.is_some() {
+            " (token required)"
+        } else {
+            ""
+        }
+    );
+    match serve(listener, Config { data_dir, token }).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Devices syncing through a server on localhost, the way the app does.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+use array30_sync_protocol::{changes_path, PullResponse, PushRequest, PushResponse, SyncState};
+use array30_sync_server::{serve, Config};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+
+async fn start(config: Config) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(serve(listener, config));
+    format!("http://{address}")
+}
+
+fn data_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("array30-sync-{test}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+/// A device's sessions by id and its sync state.
+#[derive(Default)]
+struct Device {
+    sessions: BTreeMap<String, Value>,
+    state: SyncState,
+}
+
+impl Device {
+    /// Pulls until caught up, then pushes what the server has not seen.
+    async fn sync(&mut self, client: &reqwest::Client, url: &str) {
+        loop {
+            let pull: PullResponse = client
+                .get(format!("{url}?since={}", self.state.cursor))
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+            let more = pull.more;
+            let local: BTreeSet<String> = self.sessions.keys().cloned().collect();
+            let incoming = self.state.apply(pull, &local);
+            self.sessions.extend(incoming.added);
+            self.sessions.retain(|id, _| !incoming.deleted.contains(id));
+            if !more {
+                break;
+            }
+        }
+        let changes = self.state.pending(&self.sessions);
+        let response: PushResponse = client
+            .post(url)
+            .json(&PushRequest {
+                changes: changes.clone(),
+            })
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert!(response.appended <= changes.len());
+        self.state.pushed(&changes);
+    }
+}
+
+fn session(text: &str) -> (String, Value) {
+    (text.to_string(), json!({ "exercise_text": text }))
+}
+
+#[tokio::test]
+async fn two_devices_share_sessions_and_deletions() {
+    let dir = data_dir("share");
+    let config = Config {
+        data_dir: dir.clone(),
+        token: None,
+    };
+    let url = start(config.clone()).await + &changes_path("home");
+    let client = reqwest::Client::new();
+
+    let mut phone = Device::default();
+    phone.sessions.extend([session("一"), session("二")]);
+    phone.sync(&client, &url).await;
+
+    let mut laptop = Device::default();
+    laptop.sessions.extend([session("三")]);
+    laptop.sync(&client, &url).await;
+    assert_eq!(laptop.sessions.len(), 3);
+
+    phone.sessions.remove("一");
+    phone.sync(&client, &url).await;
+    assert_eq!(phone.sessions.keys().collect::<Vec<_>>(), ["三", "二"]);
+
+    laptop.sync(&client, &url).await;
+    assert_eq!(laptop.sessions, phone.sessions);
+
+    // A restarted server reads the same history back from its file.
+    let url = start(config).await + &changes_path("home");
+    let mut tablet = Device::default();
+    tablet.sync(&client, &url).await;
+    assert_eq!(tablet.sessions, phone.sessions);
+    assert_eq!(tablet.state.cursor, 4);
+
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn token_and_space_name_are_checked() {
+    let dir = data_dir("token");
+    let base = start(Config {
+        data_dir: dir.clone(),
+        token: Some("secret".to_string()),
+    })
+    .await;
+    let client = reqwest::Client::new();
+    let url = base.clone() + &changes_path("home");
+
+    let status =
+        |request: reqwest::RequestBuilder| async move { request.send().await.unwrap().status() };
+    assert_eq!(status(client.get(&url)).await, StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        status(client.get(&url).bearer_auth("wrong")).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        status(client.get(&url).bearer_auth("secret")).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        status(
+            client
+                .get(base + &changes_path("a.b"))
+                .bearer_auth("secret")
+        )
+        .await,
+        StatusCode::BAD_REQUEST
+    );
+
+    let _ = std::fs::remove_dir_all(dir);
+}